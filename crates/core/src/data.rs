@@ -1,6 +1,8 @@
 //! Getting data from advent of code, and data required to get data from advent of code (year and
 //! session token).
 
+use std::path::PathBuf;
+
 use color_eyre::eyre;
 use jiff::civil::Time;
 use reqwest::blocking::Client;
@@ -36,6 +38,34 @@ pub fn get_session_token() -> eyre::Result<String> {
     Ok(std::env::var("SESSION_TOKEN")?)
 }
 
+/// The number of puzzles in a year of advent of code. Since 2025, there are 12 instead of 25.
+pub fn days_in_year(year: i16) -> u8 {
+    if year >= 2025 {
+        12
+    } else {
+        25
+    }
+}
+
+/// Gets the day of today's puzzle, which is only during December (in UTC-5, when puzzles unlock).
+///
+/// It can be overriden with the `ELVISH_TODAY` environment variable.
+pub fn get_today() -> Option<u8> {
+    if let Ok(day) = std::env::var("ELVISH_TODAY") {
+        return day.parse().ok();
+    }
+
+    let tz = jiff::tz::offset(-5).to_time_zone();
+    let now = jiff::Timestamp::now().to_zoned(tz);
+
+    let day = now.day() as u8;
+    if now.month() == 12 && day <= days_in_year(now.year()) {
+        Some(day)
+    } else {
+        None
+    }
+}
+
 /// Checks whether the specified is accessible.
 ///
 /// Assumes system time is correct. 
@@ -59,15 +89,16 @@ pub fn is_day_accessible(year: i16, day: u8) -> bool {
         .expect("Date should be valid")
         .with_time_zone(tz);
 
-    if now > unlocks {
-        return true;
-    } else {
-        return false;
-    }
+    now > unlocks
 }
 
 /// Get the day's [data](Day).
 pub fn get(year: i16, day: u8, session_token: &str) -> eyre::Result<Day> {
+    let days = days_in_year(year);
+    if !(1..=days).contains(&day) {
+        eyre::bail!("Day {day} doesn't exist, advent of code {year} has {days} days");
+    }
+
     if !is_day_accessible(year, day) {
         let msg = format!("Day {day} is not accessible yet!");
         tracing::warn!(msg);
@@ -75,19 +106,34 @@ pub fn get(year: i16, day: u8, session_token: &str) -> eyre::Result<Day> {
         eyre::bail!(msg);
     }
 
-    read_day(day).or_else(|_| {
+    read_cached(year, day).or_else(|_| {
         tracing::warn!("Day data not found in `.elvish`, fetching day...");
         eprintln!("Day data not found in `.elvish`, fetching day...");
         let data = fetch_day(year, day, session_token)?;
         let serialized = ron::to_string(&data)?;
 
-        std::fs::create_dir_all(PARENT_PATH)?;
-        std::fs::write(path(day), serialized)?;
+        std::fs::create_dir_all(format!("{PARENT_PATH}/{year}"))?;
+        std::fs::write(path(year, day), serialized)?;
 
         Ok(data)
     })
 }
 
+/// Gets the path of the day's input in the cache, fetching the day first if it isn't cached yet.
+///
+/// The input is cached on its own next to the day's data, so that it can be used as is (e.g.
+/// memory-mapped) instead of being deserialized.
+pub fn get_input_path(year: i16, day: u8, session_token: &str) -> eyre::Result<PathBuf> {
+    let path = input_path(year, day);
+    if !path.exists() {
+        let data = get(year, day, session_token)?;
+        std::fs::create_dir_all(format!("{PARENT_PATH}/{year}"))?;
+        std::fs::write(&path, data.input)?;
+    }
+
+    Ok(path)
+}
+
 /// Fetches the data for a day from the advent of code website. Not cached.
 pub fn fetch_day(year: i16, day: u8, session_token: &str) -> eyre::Result<Day> {
     let client = reqwest::blocking::Client::new();
@@ -144,9 +190,7 @@ fn fetch_desc(
 
     let mut descriptions = elements.map(|element| {
         let inner_html = element.get(parser).unwrap().inner_html(parser);
-        let markdown = mdka::from_html(&inner_html);
-
-        markdown
+        mdka::from_html(&inner_html)
     });
 
     let desc1 = descriptions
@@ -158,13 +202,40 @@ fn fetch_desc(
 }
 
 const PARENT_PATH: &str = ".elvish";
-fn path(day: u8) -> impl AsRef<std::path::Path> {
+fn path(year: i16, day: u8) -> impl AsRef<std::path::Path> {
+    format!("{PARENT_PATH}/{year}/day{:02}.ron", day)
+}
+
+fn input_path(year: i16, day: u8) -> PathBuf {
+    format!("{PARENT_PATH}/{year}/day{:02}.txt", day).into()
+}
+
+/// Where days were cached before the cache was split by year.
+fn legacy_path(day: u8) -> impl AsRef<std::path::Path> {
     format!("{PARENT_PATH}/day{:02}.ron", day)
 }
 
-fn read_day(day: u8) -> eyre::Result<Day> {
-    let day = std::fs::read_to_string(path(day))?;
+/// Reads the [data](Day) of a day from the cache in `.elvish`, without fetching it.
+pub fn read_cached(year: i16, day: u8) -> eyre::Result<Day> {
+    let day = std::fs::read_to_string(path(year, day)).or_else(|err| {
+        // Legacy caches don't say which year they are from, but they were fetched for `YEAR`.
+        match get_env_year() {
+            Ok(env_year) if env_year == year => std::fs::read_to_string(legacy_path(day)),
+            _ => Err(err),
+        }
+    })?;
     let day = ron::from_str(&day)?;
 
     Ok(day)
 }
+
+/// Reads the cached input of a day, e.g. for testing on the real input. The year is taken from the
+/// environment (or `.env`) if it isn't given.
+///
+/// Returns `None` if there's no year or the input hasn't been cached.
+pub fn read_cached_input(year: Option<i16>, day: u8) -> Option<String> {
+    dotenvy::dotenv().ok();
+    let year = year.or_else(|| get_env_year().ok())?;
+
+    read_cached(year, day).ok().map(|day| day.input)
+}
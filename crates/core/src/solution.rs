@@ -4,10 +4,38 @@ use std::fmt::Display;
 
 /// A solution of a part of an advent of code puzzle. 
 ///
-/// It takes the input as a string slice and returns some output that can be converted to a string.
+/// It takes the raw input as bytes and returns some output that can be converted to a string. Use
+/// [`Input`] to convert the bytes into whatever the solution works with.
 pub trait Part<const PART: u8, const DAY: u8> {
     /// Solves the puzzle for that part. 
-    fn solve(input: &str) -> impl Display;
+    fn solve(input: &[u8]) -> impl Display;
+}
+
+/// A type a solution can take as its input.
+///
+/// It is implemented for `&[u8]`, `&str` and [`Lines`](std::str::Lines). Solutions that take an
+/// `impl BufRead` don't need it, since `&[u8]` already implements [`BufRead`](std::io::BufRead).
+pub trait Input<'a>: Sized {
+    /// Converts the raw puzzle input into this type.
+    fn from_input(input: &'a [u8]) -> Self;
+}
+
+impl<'a> Input<'a> for &'a [u8] {
+    fn from_input(input: &'a [u8]) -> Self {
+        input
+    }
+}
+
+impl<'a> Input<'a> for &'a str {
+    fn from_input(input: &'a [u8]) -> Self {
+        std::str::from_utf8(input).expect("Puzzle input should be valid UTF-8")
+    }
+}
+
+impl<'a> Input<'a> for std::str::Lines<'a> {
+    fn from_input(input: &'a [u8]) -> Self {
+        <&str>::from_input(input).lines()
+    }
 }
 
 /// Solution for both parts of a day 
@@ -16,10 +44,10 @@ pub trait Part<const PART: u8, const DAY: u8> {
 /// a day. 
 pub trait Day<const DAY: u8>: Part<1, DAY> + Part<2, DAY> {
     /// Solves the puzzle for that part 1 of the day. 
-    fn part1(input: &str) -> impl Display;
+    fn part1(input: &[u8]) -> impl Display;
 
     /// Solves the puzzle for that part 2 of the day. 
-    fn part2(input: &str) -> impl Display;
+    fn part2(input: &[u8]) -> impl Display;
 }
 
 // Auto implement the `Day` trait for any type that implements the `Part` trait for a day
@@ -27,12 +55,12 @@ impl<T, const DAY: u8> Day<DAY> for T
 where
     T: Part<1, DAY> + Part<2, DAY>,
 {
-    fn part1(input: &str) -> impl Display {
+    fn part1(input: &[u8]) -> impl Display {
         <T as Part<1, DAY>>::solve(input)
     }
 
-    fn part2(input: &str) -> impl Display {
-        <T as Part<1, DAY>>::solve(input)
+    fn part2(input: &[u8]) -> impl Display {
+        <T as Part<2, DAY>>::solve(input)
     }
 }
 
@@ -44,7 +72,7 @@ where
 ///
 /// See also [`run_day`] to run the entire day. 
 pub fn run_day_part<Solutions: Part<PART, DAY>, const DAY: u8, const PART: u8>(
-    input: &str,
+    input: &[u8],
 ) -> String {
     Solutions::solve(input).to_string()
 }
@@ -52,7 +80,7 @@ pub fn run_day_part<Solutions: Part<PART, DAY>, const DAY: u8, const PART: u8>(
 /// Run the solution of both parts for a given day, returning the result as a string. 
 ///
 /// See also [`run_day_part`] to run an individual part. 
-pub fn run_day<Solutions: Day<DAY>, const DAY: u8>(input: &str) -> [String; 2] {
+pub fn run_day<Solutions: Day<DAY>, const DAY: u8>(input: &[u8]) -> [String; 2] {
     let part1 = run_day_part::<Solutions, DAY, 1>(input);
     let part2 = run_day_part::<Solutions, DAY, 2>(input);
 
@@ -89,7 +117,7 @@ pub fn run<
         + Day<24>
         + Day<25>,
 >(
-    input: &str,
+    input: &[u8],
     day: u8,
 ) -> [String; 2] {
     match day {
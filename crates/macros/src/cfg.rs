@@ -0,0 +1,197 @@
+//! Conditions for compiling days and parts, shared by every macro that gates on them.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::LitInt;
+
+/// How days and parts get selected in the crate being compiled.
+enum Mode {
+    /// With cargo features: `dayNN` for both parts of a day (together with `part1`/`part2`), or
+    /// `dayNN-P` for a single part, plus `today` and `generate-docs`.
+    Features {
+        /// The day of today's puzzle, if there is one. It's only known while compiling, so cargo
+        /// needs to recompile for the `today` feature to pick the next day.
+        today: Option<u8>,
+    },
+
+    /// With the cfgs set by `elvish::build::configure`.
+    Build,
+}
+
+/// The conditions for the days of a year.
+pub struct Cfgs {
+    mode: Mode,
+
+    /// The year, for crates with solutions for multiple years. Their days are gated separately for
+    /// each year (e.g. with `y2016-day02` instead of `day02`).
+    year: Option<i16>,
+}
+
+impl Cfgs {
+    pub fn new(year: Option<i16>) -> Self {
+        // Set by `elvish::build::configure`.
+        let mode = if std::env::var_os("ELVISH_CFG").is_some() {
+            Mode::Build
+        } else {
+            Mode::Features {
+                today: elvish_core::data::get_today(),
+            }
+        };
+
+        Cfgs { mode, year }
+    }
+
+    /// The days of the year (all 25 if the year isn't known).
+    pub fn days(&self) -> std::ops::RangeInclusive<u8> {
+        1..=self.year.map_or(25, elvish_core::data::days_in_year)
+    }
+
+    /// Condition for a feature. Cargo warns about it if the crate doesn't declare it.
+    fn feature(name: &str) -> TokenStream {
+        quote! { feature = #name }
+    }
+
+    /// Condition for a feature of a day, like `day02` or `day02-1`, prefixed with the year if
+    /// there is one (like `y2016-day02`).
+    fn day_feature(&self, day: u8, suffix: &str) -> TokenStream {
+        match self.year {
+            Some(year) => Self::feature(&format!("y{year}-day{day:0>2}{suffix}")),
+            None => Self::feature(&format!("day{day:0>2}{suffix}")),
+        }
+    }
+
+    /// Whether `day` is today's puzzle.
+    fn is_today(&self, day: u8) -> bool {
+        matches!(self.mode, Mode::Features { today: Some(today) } if today == day)
+    }
+
+    /// Makes a condition for a feature of a day false with the `today` feature, unless the day is
+    /// today's.
+    fn unless_not_today(&self, day: u8, cfg: TokenStream) -> TokenStream {
+        if self.is_today(day) {
+            cfg
+        } else {
+            let today = Self::feature("today");
+            quote! { all(not(#today), #cfg) }
+        }
+    }
+
+    /// Condition for the `dayNN` feature, which selects both parts of a day (or the ones selected
+    /// with `part1`/`part2`). The `today` feature also enables it for today's day, and disables it
+    /// for every other day.
+    fn both_parts(&self, day: u8) -> TokenStream {
+        let cfg = self.day_feature(day, "");
+        if self.is_today(day) {
+            let today = Self::feature("today");
+            quote! { any(#today, #cfg) }
+        } else {
+            self.unless_not_today(day, cfg)
+        }
+    }
+
+    /// Condition for the `dayNN-P` feature, which selects a single part of a day.
+    fn single_part(&self, day: u8, part: u8) -> TokenStream {
+        self.unless_not_today(day, self.day_feature(day, &format!("-{part}")))
+    }
+
+    /// Condition for the build cfg of the year, which is set for every year unless they are
+    /// selected with `ELVISH_YEAR`.
+    fn build_year(&self, cfg: TokenStream) -> TokenStream {
+        match self.year {
+            Some(year) => {
+                let year = year.to_string();
+                quote! { all(#cfg, any(elvish_year = "all", elvish_year = #year)) }
+            }
+            None => cfg,
+        }
+    }
+
+    /// Condition for compiling a day.
+    pub fn day(&self, day: u8) -> TokenStream {
+        match self.mode {
+            Mode::Build => {
+                let day = day.to_string();
+                self.build_year(quote! { elvish_day = #day })
+            }
+            Mode::Features { .. } => {
+                let [part1, part2] = [1, 2].map(|part| self.single_part(day, part));
+                let both = self.both_parts(day);
+                quote! { any(#both, #part1, #part2) }
+            }
+        }
+    }
+
+    /// Condition for compiling a part (either 1 or 2) of a day.
+    pub fn part(&self, day: u8, part: u8) -> TokenStream {
+        match self.mode {
+            Mode::Build => {
+                let day_part = format!("{day}-{part}");
+                self.build_year(quote! { elvish_part = #day_part })
+            }
+            Mode::Features { .. } => {
+                let both = self.both_parts(day);
+                let part_feature = Self::feature(&format!("part{part}"));
+                let single = self.single_part(day, part);
+                quote! { any(all(#both, #part_feature), #single) }
+            }
+        }
+    }
+
+    /// Condition for adding the puzzle description as docs to solutions.
+    pub fn docs(&self) -> TokenStream {
+        match self.mode {
+            Mode::Build => quote! { elvish_generate_docs },
+            Mode::Features { .. } => Self::feature("generate-docs"),
+        }
+    }
+
+    /// Makes cargo recompile when the override for today (`ELVISH_TODAY`) changes, and gives an
+    /// error if the `today` feature is enabled but there is no puzzle today.
+    pub fn today_checks(&self) -> Option<TokenStream> {
+        let Mode::Features { today } = self.mode else {
+            return None;
+        };
+
+        let no_today = today.is_none().then(|| {
+            let today = Self::feature("today");
+            quote! {
+                #[cfg(#today)]
+                compile_error!(
+                    "There is no puzzle today, so the `today` feature can't be used. Set the \
+                    `ELVISH_TODAY` environment variable to choose a day"
+                );
+            }
+        });
+
+        Some(quote! {
+            const _: Option<&str> = option_env!("ELVISH_TODAY");
+            #no_today
+        })
+    }
+}
+
+/// The optional year passed to the declaring macros, like `declare::modules!(2016)`.
+pub struct YearArg(pub Option<i16>);
+
+impl Parse for YearArg {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.is_empty() {
+            return Ok(YearArg(None));
+        }
+
+        let year = parse_year(&input.parse()?)?;
+        Ok(YearArg(Some(year)))
+    }
+}
+
+/// Parses a year, which can't be before advent of code started.
+pub fn parse_year(lit: &LitInt) -> syn::Result<i16> {
+    match lit.base10_parse()? {
+        year @ 2015.. => Ok(year),
+        other => {
+            let msg = format!("Advent of code started in 2015, so there is no year {other}");
+            Err(syn::Error::new(lit.span(), msg))
+        }
+    }
+}
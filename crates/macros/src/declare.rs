@@ -1,36 +1,49 @@
 use proc_macro::TokenStream;
 use quote::{format_ident, quote};
 
-pub fn modules() -> TokenStream {
-    (1..=25)
-        .map(|day| {
-            let day_number = format!("{:0>2}", day);
-            let day_full = format!("day{day_number}");
-            let mod_ident = format_ident!("day{day_number}");
-
-            TokenStream::from(quote! {
-                #[cfg(feature = #day_full)]
-                mod #mod_ident;
-            })
-        })
-        .collect()
+use crate::cfg::Cfgs;
+
+pub fn modules(year: Option<i16>) -> TokenStream {
+    let cfgs = Cfgs::new(year);
+
+    let modules = cfgs.days().map(|day| {
+        let day_number = format!("{:0>2}", day);
+        let mod_ident = format_ident!("day{day_number}");
+        let cfg = cfgs.day(day);
+
+        quote! {
+            #[cfg(#cfg)]
+            mod #mod_ident;
+        }
+    });
+
+    let today_checks = cfgs.today_checks();
+
+    quote! {
+        #today_checks
+        #(#modules)*
+    }
+    .into()
 }
 
-pub fn run_fn() -> TokenStream {
-    let body: proc_macro2::TokenStream = (1..=25u8)
+pub fn run_fn(year: Option<i16>) -> TokenStream {
+    let cfgs = Cfgs::new(year);
+
+    let body: proc_macro2::TokenStream = cfgs
+        .days()
         .map(|day| {
-            let day_number = format!("{:0>2}", day);
-            let day_full = format!("day{day_number}");
+            let cfg = cfgs.day(day);
+            let [part1_cfg, part2_cfg] = [1, 2].map(|part| cfgs.part(day, part));
 
             quote! {
-                #[cfg(feature = #day_full)]
+                #[cfg(#cfg)]
                 if day == #day {
-                    #[cfg(feature = "part1")]
+                    #[cfg(#part1_cfg)]
                     if part == 0 {
                         return Ok(elvish::solution::run_day_part::<Solutions, #day, 1>(input));
                     }
 
-                    #[cfg(feature = "part2")]
+                    #[cfg(#part2_cfg)]
                     if part == 1 {
                         return Ok(elvish::solution::run_day_part::<Solutions, #day, 2>(input));
                     }
@@ -40,23 +53,32 @@ pub fn run_fn() -> TokenStream {
         .collect();
 
     quote! {
-        fn run_day_part(day: u8, part: u8, input: &str) -> eyre::Result<String> {
+        pub(crate) fn run_day_part(
+            day: u8,
+            part: u8,
+            input: &[u8],
+        ) -> elvish::eyre::Result<String> {
             #body
 
-            panic!("The given day ({day}) and part ({part}) was not available");
+            elvish::eyre::bail!(
+                "The given day ({day}) and part ({}) was not available",
+                part + 1
+            );
         }
     }
     .into()
 }
 
-pub fn available_days() -> TokenStream {
-    let body: proc_macro2::TokenStream = (1..=25u8)
+pub fn available_days(year: Option<i16>) -> TokenStream {
+    let cfgs = Cfgs::new(year);
+
+    let body: proc_macro2::TokenStream = cfgs
+        .days()
         .map(|day| {
-            let day_number = format!("{:0>2}", day);
-            let day_full = format!("day{day_number}");
+            let cfg = cfgs.day(day);
 
             quote! {
-                #[cfg(feature = #day_full)]
+                #[cfg(#cfg)]
                 #day ,
             }
         })
@@ -69,3 +91,11 @@ pub fn available_days() -> TokenStream {
     }
     .into()
 }
+
+pub fn solutions_type(ty: syn::Type) -> TokenStream {
+    quote! {
+        #[doc(hidden)]
+        pub(crate) type Solutions = #ty;
+    }
+    .into()
+}
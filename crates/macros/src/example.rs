@@ -1,64 +1,412 @@
 use proc_macro::TokenStream;
-use quote::{format_ident, quote};
+use quote::{format_ident, quote, ToTokens};
 use syn::{
     parse::{Parse, ParseStream},
     punctuated::Punctuated,
-    Expr, Ident, Token,
+    Attribute, Ident, LitInt, LitStr, Token,
 };
 
-pub enum Example {
-    Single(Expr),
+mod kw {
+    syn::custom_keyword!(file);
+    syn::custom_keyword!(dir);
+    syn::custom_keyword!(day);
+}
+
+pub struct Example {
+    /// Options for every example, given as inner attributes (`#![...]`).
+    options: Options,
+    /// The day the examples are for, which the names of the constants are keyed by.
+    day: Option<u8>,
+    examples: Examples,
+}
+
+enum Examples {
+    Single(Source),
     Multiple {
         parts: Punctuated<PartExample, Token![,]>,
     },
+    /// A directory with a file for each example, named `partM_K.txt`.
+    Directory(LitStr),
 }
 
 impl Parse for Example {
     fn parse(input: ParseStream) -> syn::Result<Self> {
-        let parser = Punctuated::parse_terminated;
+        let options = Options::from_attrs(&input.call(Attribute::parse_inner)?)?;
+
+        if !(input.peek(kw::day) && input.peek2(Token![=])) {
+            let examples = input.parse()?;
+            return Ok(Self {
+                options,
+                day: None,
+                examples,
+            });
+        }
+
+        input.parse::<kw::day>()?;
+        input.parse::<Token![=]>()?;
+        let day: LitInt = input.parse()?;
+        let day_value = day.base10_parse::<u8>()?;
 
-        let Ok(parts) = input.call(parser) else {
-            return Ok(Example::Single(input.parse()?));
+        if !input.is_empty() {
+            input.parse::<Token![,]>()?;
+        }
+
+        let examples = if input.is_empty() {
+            // Shorthand for the conventional directory, `examples/dayNN`.
+            let dir = format!("examples/day{day_value:0>2}");
+            Examples::Directory(LitStr::new(&dir, day.span()))
+        } else {
+            input.parse()?
         };
 
-        Ok(Example::Multiple { parts })
+        Ok(Self {
+            options,
+            day: Some(day_value),
+            examples,
+        })
+    }
+}
+
+impl Parse for Examples {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.peek(Ident) && input.peek2(Token![:]) {
+            let parser = Punctuated::parse_terminated;
+            return Ok(Examples::Multiple {
+                parts: input.call(parser)?,
+            });
+        }
+
+        if input.peek(kw::dir) {
+            input.parse::<kw::dir>()?;
+            input.parse::<Token![=]>()?;
+            return Ok(Examples::Directory(input.parse()?));
+        }
+
+        Ok(Examples::Single(input.parse()?))
     }
 }
 
 impl Example {
     pub fn expand(self) -> TokenStream {
-        match self {
-            Example::Single(expr) => [0, 1]
+        match self.try_expand() {
+            Ok(expansion) => expansion.into(),
+            Err(err) => err.into_compile_error().into(),
+        }
+    }
+
+    fn try_expand(self) -> syn::Result<proc_macro2::TokenStream> {
+        let options = self.options;
+
+        // Each example, along with its index and whether it's the first one of its part.
+        let examples: Vec<(PartExample, u32, bool)> = match self.examples {
+            Examples::Single(source) => [0, 1]
                 .map(|part| {
-                    PartExample {
-                        part,
-                        expr: expr.clone(),
-                    }
-                    .expand(0)
+                    let source = source.clone();
+                    (PartExample { part, source }, 0, true)
                 })
-                .into_iter()
-                .collect(),
+                .into(),
 
-            Example::Multiple { parts } => {
+            Examples::Multiple { parts } => {
                 let mut part_indices = [0, 0];
 
                 parts
                     .into_iter()
                     .map(|part| {
                         let index = &mut part_indices[part.part as usize];
-                        let expansion = part.expand(*index);
+                        let example = (part, *index, *index == 0);
                         *index += 1;
-                        expansion
+                        example
                     })
                     .collect()
             }
+
+            Examples::Directory(dir) => {
+                let examples = read_directory(&dir)?;
+
+                // The numbers in file names can start anywhere, so the first example of each part
+                // (which has the lowest number, since they're sorted) gets the alias.
+                let is_first: Vec<bool> = (0..examples.len())
+                    .map(|i| i == 0 || examples[i - 1].0.part != examples[i].0.part)
+                    .collect();
+
+                examples
+                    .into_iter()
+                    .zip(is_first)
+                    .map(|((example, index), is_first)| (example, index, is_first))
+                    .collect()
+            }
+        };
+
+        let mut expansion = examples
+            .iter()
+            .map(|(example, index, is_first)| example.expand(self.day, *index, *is_first, options))
+            .collect::<syn::Result<proc_macro2::TokenStream>>()?;
+
+        if self.day.is_none() {
+            expansion.extend(expand_day_aliases(&examples));
+        }
+
+        Ok(expansion)
+    }
+}
+
+/// Generates aliases of examples that aren't keyed by day for every day, so that solutions (which
+/// look up `EXAMPLE_DAYd_PARTx`) find them. They are glob imported, so constants of examples that
+/// are keyed by day take precedence over them.
+fn expand_day_aliases(examples: &[(PartExample, u32, bool)]) -> proc_macro2::TokenStream {
+    let names: Vec<(u8, Option<u32>)> = examples
+        .iter()
+        .flat_map(|(example, index, is_first)| {
+            let general = is_first.then_some((example.part, None));
+            [Some((example.part, Some(*index))), general]
+        })
+        .flatten()
+        .collect();
+
+    let aliases = (1..=25).flat_map(|day| {
+        names.iter().map(move |&(part, index)| {
+            let alias = format_example(Some(day), part, index);
+            let ident = format_example(None, part, index);
+            quote! { pub(super) const #alias: &str = super::#ident; }
+        })
+    });
+
+    quote! {
+        #[cfg(test)]
+        #[doc(hidden)]
+        #[allow(dead_code)]
+        mod __elvish_examples_of_every_day {
+            #(#aliases)*
+        }
+
+        #[cfg(test)]
+        #[allow(unused_imports)]
+        use self::__elvish_examples_of_every_day::*;
+    }
+}
+
+/// Gets the path of a file relative to the crate's manifest.
+fn manifest_path(path: &LitStr) -> syn::Result<std::path::PathBuf> {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR")
+        .map_err(|_| syn::Error::new(path.span(), "`CARGO_MANIFEST_DIR` should be set by cargo"))?;
+
+    Ok(std::path::Path::new(&manifest_dir).join(path.value()))
+}
+
+/// Finds the examples in a directory (relative to the crate's manifest), returning them along with
+/// their index.
+fn read_directory(dir: &LitStr) -> syn::Result<Vec<(PartExample, u32)>> {
+    let error = |msg: String| syn::Error::new(dir.span(), msg);
+
+    let entries = std::fs::read_dir(manifest_path(dir)?).map_err(|err| {
+        let msg = format!("Couldn't read examples in `{}`: {err}", dir.value());
+        error(msg)
+    })?;
+
+    let mut examples = Vec::new();
+    for entry in entries {
+        let entry = entry.map_err(|err| error(err.to_string()))?;
+        let name = entry.file_name();
+        let Some((part, index)) = name.to_str().and_then(parse_file_name) else {
+            continue;
+        };
+
+        let file = format!("{}/{}", dir.value(), name.to_string_lossy());
+        let source = Source {
+            options: Options::default(),
+            kind: SourceKind::File(LitStr::new(&file, dir.span())),
+        };
+        examples.push((PartExample { part, source }, index));
+    }
+
+    if examples.is_empty() {
+        return Err(error(format!(
+            "No examples found in `{}`. They should be named like `part1_1.txt`",
+            dir.value()
+        )));
+    }
+
+    // Sorted, so that the expansion doesn't depend on the order of the file system.
+    examples.sort_by_key(|(example, index)| (example.part, *index));
+
+    Ok(examples)
+}
+
+/// Parses `partM_K.txt` into the (zero-based) part and index.
+fn parse_file_name(name: &str) -> Option<(u8, u32)> {
+    let (part, index) = name
+        .strip_prefix("part")?
+        .strip_suffix(".txt")?
+        .split_once('_')?;
+
+    let part = match part {
+        "1" => 0,
+        "2" => 1,
+        _ => return None,
+    };
+
+    let index = index.parse::<u32>().ok()?.checked_sub(1)?;
+
+    Some((part, index))
+}
+
+/// What to do with the newline at the end of an example.
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+enum TrailingNewline {
+    /// Leave the example as it is.
+    #[default]
+    Keep,
+
+    /// Make sure the example ends with a newline, like real inputs do.
+    Always,
+
+    /// Remove the newlines at the end of the example.
+    Never,
+}
+
+impl TrailingNewline {
+    fn apply(self, mut text: String) -> String {
+        match self {
+            TrailingNewline::Keep => (),
+            TrailingNewline::Always => {
+                if !text.ends_with('\n') {
+                    text.push('\n');
+                }
+            }
+            TrailingNewline::Never => text.truncate(text.trim_end_matches(['\n', '\r']).len()),
+        }
+
+        text
+    }
+}
+
+/// Options for how the text of an example is processed, given as attributes. Options that aren't
+/// set fall back to the ones given for the whole macro.
+#[derive(Clone, Copy, Default)]
+struct Options {
+    /// Whether to use inline examples as they are, instead of unindenting them.
+    raw: Option<bool>,
+    trailing_newline: Option<TrailingNewline>,
+}
+
+impl Options {
+    fn from_attrs(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut options = Self::default();
+
+        for attr in attrs {
+            if attr.path().is_ident("raw") {
+                attr.meta.require_path_only()?;
+                options.raw = Some(true);
+            } else if attr.path().is_ident("trailing_newline") {
+                let policy: Ident = attr.parse_args()?;
+                options.trailing_newline = Some(match policy.to_string().as_str() {
+                    "keep" => TrailingNewline::Keep,
+                    "always" => TrailingNewline::Always,
+                    "never" => TrailingNewline::Never,
+                    _ => {
+                        return Err(syn::Error::new(
+                            policy.span(),
+                            "Trailing newline needs to be either `keep`, `always` or `never`",
+                        ))
+                    }
+                });
+            } else {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    "Unsupported example attribute, expected `raw` or `trailing_newline(...)`",
+                ));
+            }
+        }
+
+        Ok(options)
+    }
+
+    /// Fills in the options that aren't set with the ones in `defaults`.
+    fn or(self, defaults: Self) -> Self {
+        Self {
+            raw: self.raw.or(defaults.raw),
+            trailing_newline: self.trailing_newline.or(defaults.trailing_newline),
+        }
+    }
+}
+
+/// The text of an example, along with the options for processing it.
+#[derive(Clone)]
+pub struct Source {
+    options: Options,
+    kind: SourceKind,
+}
+
+/// Where the text of an example comes from.
+#[derive(Clone)]
+enum SourceKind {
+    /// A string literal, which gets unindented (unless it is raw).
+    Inline(LitStr),
+
+    /// A file, relative to the crate's manifest, which is included as is.
+    File(LitStr),
+}
+
+impl Parse for Source {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let options = Options::from_attrs(&input.call(Attribute::parse_outer)?)?;
+
+        let kind = if input.peek(kw::file) {
+            input.parse::<kw::file>()?;
+            input.parse::<Token![=]>()?;
+            SourceKind::File(input.parse()?)
+        } else {
+            SourceKind::Inline(input.parse()?)
+        };
+
+        Ok(Self { options, kind })
+    }
+}
+
+impl Source {
+    fn expand(&self, defaults: Options) -> syn::Result<proc_macro2::TokenStream> {
+        let options = self.options.or(defaults);
+        let trailing_newline = options.trailing_newline.unwrap_or_default();
+
+        match &self.kind {
+            SourceKind::Inline(lit) => {
+                let text = match options.raw {
+                    Some(true) => lit.value(),
+                    _ => unindent::unindent(&lit.value()),
+                };
+                let text = trailing_newline.apply(text);
+
+                Ok(LitStr::new(&text, lit.span()).into_token_stream())
+            }
+
+            SourceKind::File(path) => {
+                let include = quote! {
+                    include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/", #path))
+                };
+
+                if trailing_newline == TrailingNewline::Keep {
+                    return Ok(include);
+                }
+
+                let text = std::fs::read_to_string(manifest_path(path)?).map_err(|err| {
+                    let msg = format!("Couldn't read example `{}`: {err}", path.value());
+                    syn::Error::new(path.span(), msg)
+                })?;
+                let text = trailing_newline.apply(text);
+
+                Ok(quote! {{
+                    // Only so that the file is tracked, and changes to it trigger a recompilation.
+                    const _: &str = #include;
+                    #text
+                }})
+            }
         }
     }
 }
 
 pub struct PartExample {
     part: u8,
-    expr: Expr,
+    source: Source,
 }
 
 impl Parse for PartExample {
@@ -76,9 +424,9 @@ impl Parse for PartExample {
         };
 
         let _colon: Token![:] = input.parse()?;
-        let expr = input.parse()?;
+        let source = input.parse()?;
 
-        Ok(Self { part, expr })
+        Ok(Self { part, source })
     }
 }
 
@@ -86,44 +434,55 @@ impl PartExample {
     // TODO: Naming here is ass
     /// Generates
     ///
-    /// ```rust
-    /// const EXAMPLE_PARTx_y = {expr};
+    /// ```text
+    /// const EXAMPLE_PARTx_y = {source};
     /// ```
     ///
     /// And, additionally
     ///
-    /// ```rust
+    /// ```text
     /// const EXAMPLE_PARTx = EXAMPLE_PARTx_y; // Refers to the specific example
     /// ```
     ///
-    /// if it's the first one.
-    fn expand(&self, index: u32) -> TokenStream {
-        let ident = format_example(self.part, Some(index));
+    /// if it's the first one of its part. With a day, the names are `EXAMPLE_DAYd_PARTx_y` and
+    /// `EXAMPLE_DAYd_PARTx` instead.
+    fn expand(
+        &self,
+        day: Option<u8>,
+        index: u32,
+        is_first: bool,
+        defaults: Options,
+    ) -> syn::Result<proc_macro2::TokenStream> {
+        let ident = format_example(day, self.part, Some(index));
 
-        let expr = &self.expr;
+        let source = self.source.expand(defaults)?;
         let example = quote! {
             #[cfg(test)]
-            const #ident: &str = elvish::indoc!(#expr);
+            const #ident: &str = #source;
         };
 
-        if index == 0 {
-            let ident_general = format_example(self.part, None);
-            quote! {
+        if is_first {
+            let ident_general = format_example(day, self.part, None);
+            Ok(quote! {
                 #example
 
                 #[cfg(test)]
                 const #ident_general: &str = #ident;
-            }
-            .into()
+            })
         } else {
-            example.into()
+            Ok(example)
         }
     }
 }
 
-fn format_example(part: u8, index: Option<u32>) -> Ident {
+fn format_example(day: Option<u8>, part: u8, index: Option<u32>) -> Ident {
+    let prefix = match day {
+        Some(day) => format!("EXAMPLE_DAY{day}_"),
+        None => "EXAMPLE_".to_string(),
+    };
+
     match index {
-        Some(index) => format_ident!("EXAMPLE_PART{}_{}", part + 1, index + 1),
-        None => format_ident!("EXAMPLE_PART{}", part + 1),
+        Some(index) => format_ident!("{prefix}PART{}_{}", part + 1, index + 1),
+        None => format_ident!("{prefix}PART{}", part + 1),
     }
 }
@@ -5,6 +5,7 @@
 use proc_macro::TokenStream;
 use syn::parse_macro_input;
 
+mod cfg;
 mod declare;
 mod example;
 mod solution;
@@ -12,11 +13,56 @@ mod solution;
 /// A solution of an advent of code problem. 
 ///
 /// You need to pass in `day = X` for the macro to work. If the function is named `part1` or 
-/// `part2`, the part gets set accordingly; otherwise you need to specify it as `part = Y`.
+/// `part2`, the part gets set accordingly; otherwise you need to specify it as `part = Y`. This
+/// allows giving solutions descriptive names, and having several days in the same module (give
+/// their examples with `elvish::example!(day = N, ...)` then).
+///
+/// The day is checked to exist (taking into account that years since 2025 only have 12 days, if
+/// the year is known), and registering the same day and part twice in a module gives an error
+/// pointing to both solutions. The year is known if it's passed as `year = N`, or if the `YEAR`
+/// environment variable is set while compiling (the `.env` file isn't read for this, but it can
+/// be set in `.cargo/config.toml`).
 ///
 /// You can also specify the expected result of the example given in the puzzle using `example = Z`
 /// or `example = [A, B, C, ...]` if there are multiple. The examples need to be defined somewhere
-/// using [`elvish::example!()`](example!()) for them to work. 
+/// using [`elvish::example!()`](example!()) for them to work. The expected values can be any
+/// expression (e.g. `example = "CMZ"` or `example = -3`), and are compared with the output of the
+/// solution by their `Display` representation, so their types don't need to match exactly.
+///
+/// Similarly, the answer accepted by advent of code can be given with `answer = X`. This generates
+/// a test that runs the solution on the cached input (ignored by default, run it with `cargo test
+/// -- --ignored`), which is skipped if the input hasn't been cached.
+///
+/// With the `check` feature of elvish, `check_against = other` and `gen = gen_input` generate a
+/// test checking that the solution agrees with `other` (e.g. a brute force solution) on random
+/// inputs, generated by the proptest strategy `gen_input()` returns. `other` takes its input the
+/// same way as the solution. See `elvish::check`.
+///
+/// By default, the solution is registered for `crate::Solutions`. For crates with solutions for
+/// multiple years, pass `year = N` to register it for `crate::yearN::Solutions` instead. Any
+/// other type can be used with `target = path::to::Type`, or for every solution at once with
+/// [`solutions_type!()`](solutions_type!()). If the type doesn't exist, the error points to it:
+///
+/// ```compile_fail,E0412
+/// struct Solutions;
+///
+/// #[elvish::solution(day = 1, target = Solutoins)]
+/// fn part1(input: &str) -> usize {
+///     input.len()
+/// }
+/// # fn main() {}
+/// ```
+///
+/// The `Part` implementation and tests are only compiled if the part is selected (see
+/// [`declare_run_fn!()`](declare_run_fn!())), so unfinished parts don't end up in the binary.
+///
+/// With the `generate-docs` feature of your crate enabled, the puzzle description is added as the
+/// function's docs, if the year is known and the day has been cached (nothing is fetched while
+/// compiling).
+///
+/// The function can take its input as `&str`, `&[u8]`, [`Lines`](std::str::Lines) or
+/// `impl BufRead`, and the macro adapts the raw input accordingly (see
+/// `elvish::solution::Input`).
 ///
 /// At the end of the day, this macro is mostly to reduce boilerplate but it's easily expandable by
 /// hand. 
@@ -42,35 +88,47 @@ mod solution;
 ///         })
 ///         .sum()
 /// }
+/// # fn main() {}
 /// ```
 ///
 /// which generates:
 /// 
 /// ```rust
 /// # struct Solutions;
-/// # const EXAMPLE_PART1: &str = "yo";
+/// # const EXAMPLE_DAY1_PART1: &str = "1abc2";
 /// impl elvish::solution::Part<1, 1> for crate::Solutions {
-///     fn solve(input: &str) -> impl std::fmt::Display {
-///         part1(input)
+///     fn solve(input: &[u8]) -> impl std::fmt::Display {
+///         part1(<&str as elvish::solution::Input>::from_input(input))
 ///     }
 /// }
 /// 
 /// #[test]
 /// fn part1_example() {
-///     assert_eq!(part1(EXAMPLE_PART1), 142)
+///     // Snapshots are only recorded in example tests.
+///     let output = elvish::snapshot::record(module_path!(), "part1_example", || {
+///         part1(<&str as elvish::solution::Input>::from_input(EXAMPLE_DAY1_PART1.as_bytes())).to_string()
+///     });
+///     assert_eq!(output, "142")
 /// }
 ///
 /// fn part1(input: &str) -> u32 {
-/// // --snip--
+///     // --snip--
+/// #   todo!()
 /// }
+/// # fn main() {}
 /// ```
+// The expansion above shows the generated test, which isn't run as part of the docs.
+#[allow(clippy::test_attr_in_doctest)]
 #[proc_macro_attribute]
 pub fn solution(attr: TokenStream, item: TokenStream) -> TokenStream {
     solution::expand(attr, item)
 }
 
 /// Defines examples given in advent of code puzzles. The strings in the example are unindented
-/// using [`indoc`](https://docs.rs/indoc). 
+/// like [`indoc`](https://docs.rs/indoc) does, unless they are [raw](#raw-examples). 
+///
+/// Examples can also be read from files (relative to the crate's manifest), which are included as
+/// they are. See [below](#examples-in-files).
 ///
 /// There are three cases that make up 90% of examples in advent of code, which this macro
 /// addresses. Namely:
@@ -137,6 +195,80 @@ pub fn solution(attr: TokenStream, item: TokenStream) -> TokenStream {
 ///     ",
 /// );
 /// ```
+///
+/// # Examples in files
+///
+/// Big examples, or examples where whitespace matters, can be put in files instead. Any of the
+/// strings above can be replaced by `file = "path"`:
+///
+/// ```rust
+/// elvish::example!(
+///     part1: file = "tests/fixtures/day05_1.txt",
+///     part2: "
+///         SMALLER
+///         EXAMPLE
+///     ",
+/// );
+/// ```
+///
+/// Alternatively, all examples of a day can be put in a directory with files named `partM_K.txt`,
+/// for the `K`th example of part `M` (so `part1_1.txt`, `part1_2.txt`, `part2_1.txt`, etc.):
+///
+/// ```rust
+/// elvish::example!(dir = "tests/fixtures/day05");
+/// ```
+///
+/// For the conventional `examples/dayNN` directory, this can be shortened to
+/// `elvish::example!(day = 5)`, which also keys the examples by day (see
+/// [below](#several-days-in-one-module)). If there is no `_1` file for a part, `EXAMPLE_PARTn`
+/// refers to its example with the lowest number.
+///
+/// Note that cargo doesn't know to recompile when files are added to the directory (only when they
+/// are modified), so you might need to `touch` the file that uses the macro.
+///
+/// # Several days in one module
+///
+/// The examples are defined as constants named `EXAMPLE_PARTn` (and `EXAMPLE_PARTn_k` for the
+/// `k`th one), which solutions of any day in the module use. To have several days in the same
+/// module, start each day's examples with `day = N`. Their constants are then named
+/// `EXAMPLE_DAYN_PARTn` instead, and are only used by the solutions of that day:
+///
+/// ```rust
+/// elvish::example!(day = 1, "
+///     EXAMPLE
+///     OF DAY 1
+/// ");
+///
+/// elvish::example!(
+///     day = 2,
+///     part1: "EXAMPLE OF DAY 2",
+///     part2: file = "tests/fixtures/day05_2.txt",
+/// );
+/// ```
+///
+/// # Raw examples
+///
+/// Unindenting breaks examples where leading whitespace matters. Marking an example with `#[raw]`
+/// uses the string verbatim instead.
+///
+/// Real inputs always end with a newline, which examples usually don't. This can be changed with
+/// `#[trailing_newline(always)]`, which adds it if it's missing, or `#[trailing_newline(never)]`,
+/// which removes it. The default is `keep`, which leaves the example as it is. This also applies
+/// to examples in files.
+///
+/// Options given as inner attributes at the start of the macro apply to every example, unless an
+/// example overrides them:
+///
+/// ```rust
+/// elvish::example!(
+///     #![trailing_newline(always)]
+///
+///     part1: #[raw] "  ..#
+///   .#.",
+///
+///     part2: #[trailing_newline(keep)] file = "tests/fixtures/day05_2.txt",
+/// );
+/// ```
 #[proc_macro]
 pub fn example(input: TokenStream) -> TokenStream {
     parse_macro_input!(input as example::Example).expand()
@@ -144,7 +276,25 @@ pub fn example(input: TokenStream) -> TokenStream {
 
 /// Declare modules for each day of advent of code.
 ///
-/// Expands to 
+/// With the `today` feature, only today's module is declared, regardless of the other features.
+/// Today's puzzle is found while compiling (see `elvish::data::get_today`), and can be overriden
+/// with the `ELVISH_TODAY` environment variable (cargo recompiles when it changes, but not when
+/// the date does). This also applies to [`declare_run_fn!()`](declare_run_fn!()) and
+/// [`available_days!()`](available_days!()).
+///
+/// A day is also compiled if one of its parts is selected with a `dayNN-P` feature. The crate
+/// needs to declare all of these features, since cargo warns about unexpected cfgs otherwise.
+///
+/// If the crate's build script calls `elvish::build::configure`, days are gated on the
+/// `elvish_day = "N"` cfg it sets instead of features (and parts on `elvish_part = "N-P"`).
+///
+/// For crates with solutions for multiple years, pass the year of the module (e.g.
+/// `declare::modules!(2016)`), and the same one to [`declare_run_fn!()`](declare_run_fn!()) and
+/// [`available_days!()`](available_days!()). Its days are then gated on features prefixed with the
+/// year, like `y2016-day02` or `y2016-day02-1` (and on `ELVISH_YEAR` with the build script), and
+/// only go up to 12 for years that have 12 days.
+///
+/// Without any of this, expands to 
 /// 
 /// ```rust
 /// #[cfg(feature="day01")]
@@ -157,26 +307,30 @@ pub fn example(input: TokenStream) -> TokenStream {
 /// // etc...
 /// ```
 #[proc_macro]
-pub fn declare_modules(_input: TokenStream) -> TokenStream {
-    declare::modules()
+pub fn declare_modules(input: TokenStream) -> TokenStream {
+    declare::modules(parse_macro_input!(input as cfg::YearArg).0)
 }
 
 /// Declare a function that can run advent of code solutions dynamically based on the
 /// aviable (think, solved) days.
 ///
+/// Parts are selected for all days with the `part1` and `part2` features, or for a single day
+/// with `dayNN-P` (e.g. `day04-2`).
+///
 /// Expands to 
 /// 
 /// ```rust
-/// fn run_day_part(day: u8, part: u8, input: &str) -> eyre::Result<String> {
+/// # struct Solutions;
+/// pub(crate) fn run_day_part(day: u8, part: u8, input: &[u8]) -> elvish::eyre::Result<String> {
 ///     #[cfg(feature = "day01")]
 ///     if day == 01 {
 ///         #[cfg(feature = "part1")]
 ///         if part == 0 {
-///             return Ok(elvish::solution::run_day_part::<Solutions, 17u8, 1>(input));
+///             return Ok(elvish::solution::run_day_part::<Solutions, 1u8, 1>(input));
 ///         }
 ///         #[cfg(feature = "part2")]
 ///         if part == 1 {
-///             return Ok(elvish::solution::run_day_part::<Solutions, 17u8, 2>(input));
+///             return Ok(elvish::solution::run_day_part::<Solutions, 1u8, 2>(input));
 ///         }
 ///     }
 ///
@@ -184,20 +338,25 @@ pub fn declare_modules(_input: TokenStream) -> TokenStream {
 ///     if day == 02 {
 ///         #[cfg(feature = "part1")]
 ///         if part == 0 {
-///             return Ok(elvish::solution::run_day_part::<Solutions, 17u8, 1>(input));
+///             return Ok(elvish::solution::run_day_part::<Solutions, 2u8, 1>(input));
 ///         }
 ///         #[cfg(feature = "part2")]
 ///         if part == 1 {
-///             return Ok(elvish::solution::run_day_part::<Solutions, 17u8, 2>(input));
+///             return Ok(elvish::solution::run_day_part::<Solutions, 2u8, 2>(input));
 ///         }
 ///     }
+///
+///     // etc...
+///
+///     elvish::eyre::bail!(
+///         "The given day ({day}) and part ({}) was not available",
+///         part + 1
+///     );
 /// }
-/// 
-/// // etc...
 /// ```
 #[proc_macro]
-pub fn declare_run_fn(_input: TokenStream) -> TokenStream {
-    declare::run_fn()
+pub fn declare_run_fn(input: TokenStream) -> TokenStream {
+    declare::run_fn(parse_macro_input!(input as cfg::YearArg).0)
 }
 
 /// Declares an array of available days, based on feature flags.
@@ -205,8 +364,8 @@ pub fn declare_run_fn(_input: TokenStream) -> TokenStream {
 /// Expands to 
 /// 
 /// ```rust
+/// # let _: [u8; 0] =
 /// [
-///
 ///     #[cfg(feature="day01")]
 ///     1,
 ///     #[cfg(feature="day02")]
@@ -216,9 +375,25 @@ pub fn declare_run_fn(_input: TokenStream) -> TokenStream {
 ///
 ///     // etc...
 /// ]
+/// # ;
+/// ```
+#[proc_macro]
+pub fn available_days(input: TokenStream) -> TokenStream {
+    declare::available_days(parse_macro_input!(input as cfg::YearArg).0)
+}
+
+/// Declares the type solutions get registered for, when it isn't a `Solutions` struct at the root
+/// of the crate (or of the `yearN` module, for crates with multiple years).
+///
+/// It needs to be invoked where `Solutions` would have been declared.
 ///
+/// Expands to
+///
+/// ```rust
+/// # mod path { pub mod to { pub struct Type; } }
+/// pub(crate) type Solutions = path::to::Type;
 /// ```
 #[proc_macro]
-pub fn available_days(_input: TokenStream) -> TokenStream {
-    declare::available_days()
+pub fn solutions_type(input: TokenStream) -> TokenStream {
+    declare::solutions_type(parse_macro_input!(input as syn::Type))
 }
@@ -1,26 +1,75 @@
 use indoc::formatdoc;
 use proc_macro::TokenStream;
 use quote::ToTokens;
-use quote::{format_ident, quote};
+use quote::{format_ident, quote, quote_spanned};
 use syn::meta::ParseNestedMeta;
 use syn::parse::{Parse, ParseStream};
 use syn::punctuated::Punctuated;
-use syn::{bracketed, parse_macro_input, Ident, ItemFn, LitInt, Token};
+use syn::spanned::Spanned;
+use syn::{
+    bracketed, parse_macro_input, token, Expr, FnArg, Ident, ItemFn, Lit, LitInt, Path, Signature,
+    Token, Type, UnOp,
+};
 
+use crate::cfg::{self, Cfgs};
+
+/// How the raw input bytes get passed to the solution function.
+enum InputKind {
+    /// The function takes an `impl Trait` (e.g. `impl BufRead`), which `&[u8]` satisfies directly.
+    Bytes,
+
+    /// The function takes a concrete type, which gets converted with `elvish::solution::Input`.
+    Converted(Box<Type>),
+}
+
+impl InputKind {
+    fn from_signature(sig: &Signature) -> syn::Result<Self> {
+        let mut inputs = sig.inputs.iter();
+        let (Some(input), None) = (inputs.next(), inputs.next()) else {
+            return Err(syn::Error::new(
+                sig.inputs.span(),
+                "Solutions need to take exactly one argument (the puzzle input)",
+            ));
+        };
+
+        match input {
+            FnArg::Receiver(receiver) => Err(syn::Error::new(
+                receiver.span(),
+                "Solutions can't take `self`",
+            )),
+            FnArg::Typed(arg) => match *arg.ty {
+                Type::ImplTrait(_) => Ok(Self::Bytes),
+                _ => Ok(Self::Converted(arg.ty.clone())),
+            },
+        }
+    }
+
+    /// Generates a call of `function` with `bytes` (an expression of type `&[u8]`) adapted to the
+    /// function's input type.
+    fn call(
+        &self,
+        function: &impl ToTokens,
+        bytes: proc_macro2::TokenStream,
+    ) -> proc_macro2::TokenStream {
+        match self {
+            Self::Bytes => quote! { #function(#bytes) },
+            Self::Converted(ty) => quote_spanned! { ty.span() =>
+                #function(<#ty as ::elvish::solution::Input>::from_input(#bytes))
+            },
+        }
+    }
+}
+
+/// The expected answers for the examples of a part. They can be any expression, since they are
+/// compared to the solution's output by their [`Display`](std::fmt::Display) representation.
 enum ExpectedExample {
-    Single(LitInt),
-    Multiple {
-        values: Punctuated<LitInt, Token![,]>,
-    },
+    Single(Expr),
+    Multiple { values: Punctuated<Expr, Token![,]> },
 }
 
 impl Parse for ExpectedExample {
     fn parse(input: ParseStream) -> syn::Result<Self> {
-        // let parser = Punctuated::parse_terminated;
-
-        let output = if let Ok(value) = input.parse() {
-            ExpectedExample::Single(value)
-        } else {
+        let output = if input.peek(token::Bracket) {
             let bracket_content;
             let _bracket = bracketed!(bracket_content in input);
             let parser = Punctuated::parse_terminated;
@@ -28,6 +77,8 @@ impl Parse for ExpectedExample {
             ExpectedExample::Multiple {
                 values: bracket_content.call(parser)?,
             }
+        } else {
+            ExpectedExample::Single(input.parse()?)
         };
 
         Ok(output)
@@ -35,62 +86,125 @@ impl Parse for ExpectedExample {
 }
 
 impl ExpectedExample {
-    fn test_fn_ident(part: u8, index: Option<usize>) -> Ident {
+    fn test_fn_ident(fn_name: &Ident, index: Option<usize>) -> Ident {
         match index {
-            Some(i) => format_ident!("part{part}_example_{}", i + 1),
-            None => format_ident!("part{part}_example"),
+            Some(i) => format_ident!("{fn_name}_example_{}", i + 1),
+            None => format_ident!("{fn_name}_example"),
         }
     }
 
-    fn example_ident(part: u8, index: Option<usize>) -> Ident {
+    /// The name of the example's constant. Examples are keyed by day, so that several days can be
+    /// in the same module (`elvish::example!` without a day defines them for every day).
+    fn example_ident(day: u8, part: u8, index: Option<usize>) -> Ident {
         match index {
-            Some(i) => format_ident!("EXAMPLE_PART{part}_{}", i + 1),
-            None => format_ident!("EXAMPLE_PART{part}"),
+            Some(i) => format_ident!("EXAMPLE_DAY{day}_PART{part}_{}", i + 1),
+            None => format_ident!("EXAMPLE_DAY{day}_PART{part}"),
         }
     }
 
     fn expand_single(
-        value: LitInt,
+        value: Expr,
         fn_name: &Ident,
+        input_kind: &InputKind,
+        day: u8,
         part: u8,
         index: Option<usize>,
+        cfg: &proc_macro2::TokenStream,
     ) -> proc_macro2::TokenStream {
-        let test_fn_ident = Self::test_fn_ident(part, index);
-        let example_ident = Self::example_ident(part, index);
+        let test_fn_ident = Self::test_fn_ident(fn_name, index);
+        let example_ident = Self::example_ident(day, part, index);
+        let call = input_kind.call(fn_name, quote! { #example_ident.as_bytes() });
+        let expected = expected_string(&value);
 
         quote! {
+            #[cfg(#cfg)]
             #[test]
             fn #test_fn_ident() {
-                assert_eq!(#fn_name(#example_ident), #value)
+                // Snapshots are only recorded in example tests.
+                let output = ::elvish::snapshot::record(
+                    module_path!(),
+                    stringify!(#test_fn_ident),
+                    || ::std::string::ToString::to_string(&#call),
+                );
+                assert_eq!(output, #expected)
             }
         }
     }
 
-    fn expand(self, part: u8, fn_name: &Ident) -> proc_macro2::TokenStream {
-        let expand = move |value, index| Self::expand_single(value, fn_name, part, index);
+    fn expand(
+        self,
+        day: u8,
+        part: u8,
+        fn_name: &Ident,
+        input_kind: &InputKind,
+        cfg: &proc_macro2::TokenStream,
+    ) -> proc_macro2::TokenStream {
+        let expand = move |value, index| {
+            Self::expand_single(value, fn_name, input_kind, day, part, index, cfg)
+        };
         match self {
             Self::Single(value) => expand(value, None),
             Self::Multiple { values } => values
                 .into_iter()
                 .enumerate()
-                .map(|(i, value)| (expand.clone())(value, Some(i)))
+                .map(|(i, value)| expand(value, Some(i)))
                 .collect(),
         }
     }
 }
 
+/// Generates the expected output as a string. Integer literals are converted directly, since
+/// without the type of the output they would be inferred as `i32` (and might not fit).
+fn expected_string(value: &Expr) -> proc_macro2::TokenStream {
+    match integer_digits(value) {
+        Some(digits) => quote! { #digits },
+        None => quote! { ::std::string::ToString::to_string(&#value) },
+    }
+}
+
+fn integer_digits(value: &Expr) -> Option<String> {
+    match value {
+        Expr::Lit(expr) => match &expr.lit {
+            Lit::Int(lit) => Some(lit.base10_digits().to_string()),
+            _ => None,
+        },
+        Expr::Unary(expr) if matches!(expr.op, UnOp::Neg(_)) => {
+            Some(format!("-{}", integer_digits(&expr.expr)?))
+        }
+        _ => None,
+    }
+}
+
 #[derive(Default)]
 struct Args {
+    year: Option<LitInt>,
     day: Option<LitInt>,
+    part: Option<LitInt>,
+    target: Option<Path>,
     expected_example: Option<ExpectedExample>,
+    answer: Option<Expr>,
+    check_against: Option<Path>,
+    gen: Option<Path>,
 }
 
 impl Args {
     fn parse(&mut self, meta: ParseNestedMeta) -> syn::Result<()> {
-        if meta.path.is_ident("day") {
+        if meta.path.is_ident("year") {
+            self.year = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("day") {
             self.day = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("part") {
+            self.part = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("target") {
+            self.target = Some(meta.value()?.parse()?);
         } else if meta.path.is_ident("example") {
             self.expected_example = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("answer") {
+            self.answer = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("check_against") {
+            self.check_against = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("gen") {
+            self.gen = Some(meta.value()?.parse()?);
         } else {
             return Err(meta.error("unsupported `solution` property"));
         }
@@ -105,58 +219,260 @@ pub fn expand(attrs: TokenStream, item: TokenStream) -> TokenStream {
     let arg_parser = syn::meta::parser(|meta| args.parse(meta));
     parse_macro_input!(attrs with arg_parser);
 
-    if args.day.is_none() {
+    let Some(day) = args.day else {
         return quote! {
             compile_error!("Day needs to be set in `elvish::solution` macro");
         }
         .into();
+    };
+
+    let explicit_year = match args.year.as_ref().map(cfg::parse_year).transpose() {
+        Ok(year) => year,
+        Err(err) => return err.into_compile_error().into(),
+    };
+    let year = explicit_year.or_else(get_env_year);
+
+    if let Err(err) = check_day(&day, year) {
+        return err.into_compile_error().into();
     }
 
     let function = parse_macro_input!(item as ItemFn);
 
     let fn_name = function.sig.ident.clone();
-    let part: u8 = match fn_name.to_string().as_str() {
-        "part1" => 1,
-        "part2" => 2,
-        other => {
-            let msg = format!(
-                "Function name for solution needs to be either `part1` or `part2`, was {other}"
-            );
-            return quote! { compile_error!(#msg) }.into();
-        }
+    let part = match get_part(&fn_name, args.part.as_ref()) {
+        Ok(part) => part,
+        Err(err) => return err.into_compile_error().into(),
     };
 
-    let doc = get_doc(args.day.as_ref());
+    let input_kind = match InputKind::from_signature(&function.sig) {
+        Ok(kind) => kind,
+        Err(err) => return err.into_compile_error().into(),
+    };
+
+    // Only solutions with an explicit year are gated by year, since crates with a single year
+    // don't need to pass it.
+    let cfgs = Cfgs::new(explicit_year);
+    let doc = get_doc(year, &day, &cfgs);
+
+    // Only the parts selected to be compiled are registered (and tested), so that unfinished ones
+    // don't end up in the binary.
+    let day_number = match day.base10_parse() {
+        Ok(day) => day,
+        Err(err) => return err.into_compile_error().into(),
+    };
+    let cfg = cfgs.part(day_number, part);
 
     let item = function.into_token_stream();
 
-    let day = args.day;
+    let target = match (&args.target, &args.year) {
+        (Some(target), _) => target.to_token_stream(),
+        // Each year has its own `Solutions`, in a module named after it.
+        (None, Some(year)) => {
+            let module = format_ident!("year{}", year.base10_digits());
+            quote! { crate::#module::Solutions }
+        }
+        (None, None) => quote! { crate::Solutions },
+    };
+
+    // Checks that the target exists on its own (even if the part isn't compiled), so that the
+    // error points to the `target` argument instead of to the generated impl.
+    let target_span = args
+        .target
+        .as_ref()
+        .map_or_else(proc_macro2::Span::call_site, |target| target.span());
+    let target_check = quote_spanned! { target_span =>
+        const _: fn() = || {
+            let _: ::core::marker::PhantomData<#target>;
+        };
+    };
+
+    let call = input_kind.call(&fn_name, quote! { input });
     let registration = quote! {
-        impl ::elvish::solution::Part<#part, #day> for crate::Solutions {
-            fn solve(input: &str) -> impl ::std::fmt::Display {
-                #fn_name(input)
+        #[cfg(#cfg)]
+        impl ::elvish::solution::Part<#part, #day> for #target {
+            fn solve(input: &[u8]) -> impl ::std::fmt::Display {
+                #call
             }
         }
     };
 
-    let example = args.expected_example.map(|e| e.expand(part, &fn_name));
+    // Registering the same part twice already fails because of the conflicting `Part` impls, but
+    // in the same module this gives an error that says what's wrong and points to both functions.
+    // It's a constant in the module rather than in an impl, which works for any target type.
+    // The name has the function's span, which is where "defined multiple times" errors point.
+    let duplicate_marker = match explicit_year {
+        Some(year) => format_ident!(
+            "YEAR_{year}_DAY_{}_PART_{part}_IS_SOLVED_MORE_THAN_ONCE",
+            day.base10_digits(),
+            span = fn_name.span()
+        ),
+        None => format_ident!(
+            "DAY_{}_PART_{part}_IS_SOLVED_MORE_THAN_ONCE",
+            day.base10_digits(),
+            span = fn_name.span()
+        ),
+    };
+    let duplicate_check = quote_spanned! { fn_name.span() =>
+        #[cfg(#cfg)]
+        #[doc(hidden)]
+        #[allow(dead_code)]
+        const #duplicate_marker: () = ();
+    };
+
+    let example = args
+        .expected_example
+        .map(|e| e.expand(day_number, part, &fn_name, &input_kind, &cfg));
+
+    let real = args.answer.map(|answer| {
+        let test_fn_ident = format_ident!("{fn_name}_real");
+        let call = input_kind.call(&fn_name, quote! { input.as_bytes() });
+        let expected = expected_string(&answer);
+        let year = match &args.year {
+            Some(year) => quote! { Some(#year) },
+            None => quote! { None },
+        };
+
+        quote! {
+            #[cfg(#cfg)]
+            #[test]
+            #[ignore = "runs on the real input, which might not be cached"]
+            fn #test_fn_ident() {
+                let Some(input) = ::elvish::data::read_cached_input(#year, #day) else {
+                    eprintln!("Skipping, since the input for day {} isn't cached", #day);
+                    return;
+                };
+
+                assert_eq!(::std::string::ToString::to_string(&#call), #expected)
+            }
+        }
+    });
+
+    let check = match (args.check_against, args.gen) {
+        (Some(reference), Some(gen)) => {
+            let test_fn_ident = format_ident!("{fn_name}_check");
+            let call = input_kind.call(&fn_name, quote! { input });
+            // The reference solution takes its input the same way as the solution.
+            let reference_call = input_kind.call(&reference, quote! { input });
+
+            // Goes through `check_test!` so that a missing `check` feature gives a clear error.
+            // It's only expanded for tests, since the feature is usually only enabled for them.
+            Some(quote! {
+                #[cfg(all(test, #cfg))]
+                ::elvish::check_test! {
+                    #[test]
+                    fn #test_fn_ident() {
+                        ::elvish::check::check_against(
+                            concat!(module_path!(), "::", stringify!(#fn_name)),
+                            #gen(),
+                            |input| #call,
+                            |input| #reference_call,
+                        )
+                    }
+                }
+            })
+        }
+        (None, None) => None,
+        (Some(reference), None) => {
+            let msg = "`check_against` needs a function generating inputs, given with `gen = ...`";
+            return syn::Error::new(reference.span(), msg)
+                .into_compile_error()
+                .into();
+        }
+        (None, Some(gen)) => {
+            let msg = "`gen` needs a solution to check against, given with `check_against = ...`";
+            return syn::Error::new(gen.span(), msg).into_compile_error().into();
+        }
+    };
 
     quote! {
         #doc
+        #[cfg_attr(not(#cfg), allow(dead_code))]
         #item
 
+        #target_check
         #registration
+        #duplicate_check
 
         #example
+        #real
+        #check
     }
     .into()
 }
 
-fn get_doc(day: Option<&LitInt>) -> Option<proc_macro2::TokenStream> {
-    dotenvy::dotenv().ok()?;
-    let session_token = elvish_core::data::get_session_token().ok()?;
-    let year = elvish_core::data::get_env_year().ok()?;
-    let data = elvish_core::data::get(year, day?.base10_parse().ok()?, &session_token).ok()?;
+/// Gets which part a solution is for, either from the `part` argument or from the function name.
+fn get_part(fn_name: &Ident, arg: Option<&LitInt>) -> syn::Result<u8> {
+    let from_name = match fn_name.to_string().as_str() {
+        "part1" => Some(1),
+        "part2" => Some(2),
+        _ => None,
+    };
+
+    let Some(arg) = arg else {
+        return from_name.ok_or_else(|| {
+            let msg = format!(
+                "Can't tell which part `{fn_name}` solves. Either name it `part1` or `part2`, or \
+                specify it with `part = 1` or `part = 2`"
+            );
+            syn::Error::new(fn_name.span(), msg)
+        });
+    };
+
+    let part = match arg.base10_parse()? {
+        part @ (1 | 2) => part,
+        other => {
+            let msg = format!("Part needs to be either 1 or 2, was {other}");
+            return Err(syn::Error::new(arg.span(), msg));
+        }
+    };
+
+    match from_name {
+        Some(from_name) if from_name != part => {
+            let msg = format!(
+                "Ambiguous part: `{fn_name}` is named after part {from_name}, but `part = {part}` \
+                was given"
+            );
+            Err(syn::Error::new(arg.span(), msg))
+        }
+        _ => Ok(part),
+    }
+}
+
+/// Gets the year from the `YEAR` environment variable, if set, for solutions that don't pass it.
+///
+/// The `.env` file isn't read, since expanding a macro shouldn't depend on files cargo doesn't
+/// know about.
+fn get_env_year() -> Option<i16> {
+    elvish_core::data::get_env_year().ok()
+}
+
+/// Checks that the day exists, which depends on the year if it is known.
+fn check_day(day: &LitInt, year: Option<i16>) -> syn::Result<()> {
+    let days = year.map_or(25, elvish_core::data::days_in_year);
+    let value: u8 = day.base10_parse()?;
+
+    if (1..=days).contains(&value) {
+        return Ok(());
+    }
+
+    let msg = match year {
+        Some(year) if days != 25 => format!(
+            "Day needs to be between 1 and {days} (advent of code {year} has {days} days), was \
+            {value}"
+        ),
+        _ => format!("Day needs to be between 1 and 25, was {value}"),
+    };
+
+    Err(syn::Error::new(day.span(), msg))
+}
+
+/// Gets the puzzle description from the cache, to show it as the solution's docs. It is only
+/// attached when the `generate-docs` feature (or the equivalent build cfg) is enabled.
+///
+/// This never fetches the puzzle, since doing network requests in rustc makes builds slow (and
+/// fail when offline). Running the day once is enough to cache it.
+fn get_doc(year: Option<i16>, day: &LitInt, cfgs: &Cfgs) -> Option<proc_macro2::TokenStream> {
+    let data = elvish_core::data::read_cached(year?, day.base10_parse().ok()?).ok()?;
 
     let doc_comment = formatdoc!(
         "
@@ -173,14 +489,12 @@ fn get_doc(day: Option<&LitInt>) -> Option<proc_macro2::TokenStream> {
             ```
         ",
         data.description_1,
-        data.description_2
-            .as_ref()
-            .map(|v| v.as_str())
-            .unwrap_or(""),
+        data.description_2.as_deref().unwrap_or(""),
         data.input,
     );
 
+    let cfg = cfgs.docs();
     Some(quote! {
-        #[doc = #doc_comment]
+        #[cfg_attr(#cfg, doc = #doc_comment)]
     })
 }
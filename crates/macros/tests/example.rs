@@ -0,0 +1,48 @@
+//! Checks the constants `example!` generates.
+
+elvish::example!(dir = "tests/fixtures/numbered");
+
+#[test]
+fn directory_without_first_example_aliases_lowest() {
+    assert_eq!(EXAMPLE_PART1, "second\n");
+    assert_eq!(EXAMPLE_PART1_2, "second\n");
+    assert_eq!(EXAMPLE_PART1_3, "third\n");
+    assert_eq!(EXAMPLE_PART2, "fourth\n");
+    assert_eq!(EXAMPLE_PART2_4, "fourth\n");
+}
+
+#[test]
+fn examples_without_a_day_are_aliased_for_every_day() {
+    assert_eq!(EXAMPLE_DAY1_PART1, EXAMPLE_PART1);
+    assert_eq!(EXAMPLE_DAY25_PART2_4, EXAMPLE_PART2_4);
+}
+
+mod several_days {
+    elvish::example!(day = 1, "first");
+    elvish::example!(
+        day = 2,
+        part1: "second",
+        part2: "third",
+        part2: "fourth",
+    );
+
+    #[test]
+    fn examples_are_keyed_by_day() {
+        assert_eq!(EXAMPLE_DAY1_PART1, "first");
+        assert_eq!(EXAMPLE_DAY1_PART2, "first");
+        assert_eq!(EXAMPLE_DAY2_PART1, "second");
+        assert_eq!(EXAMPLE_DAY2_PART2, "third");
+        assert_eq!(EXAMPLE_DAY2_PART2_2, "fourth");
+    }
+}
+
+mod keyed_over_every_day {
+    elvish::example!("any day");
+    elvish::example!(day = 3, "day 3");
+
+    #[test]
+    fn examples_with_a_day_take_precedence() {
+        assert_eq!(EXAMPLE_DAY3_PART1, "day 3");
+        assert_eq!(EXAMPLE_DAY4_PART1, "any day");
+    }
+}
@@ -0,0 +1,233 @@
+//! Compares the searches written by hand in days 10, 17 and 23 with the same solutions written
+//! with `elvish::search`.
+//!
+//! The real inputs can't be committed, so the inputs are generated with the same shape and size.
+//! Run it with `cargo bench`.
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use elvish::grid::{Dir, Grid, Pos};
+use elvish::search;
+use elvish::solution::run_day_part;
+
+// The solutions register themselves for `crate::Solutions`, so they are included here as they are
+// and run through their `Part` implementations.
+pub struct Solutions;
+
+#[path = "../src/solutions/day10.rs"]
+mod day10;
+#[path = "../src/solutions/day17.rs"]
+mod day17;
+#[path = "../src/solutions/day23.rs"]
+mod day23;
+
+/// Day 10 part 1: the farthest tile of the loop from the start.
+fn day10_with_search(input: &str) -> usize {
+    let pipes = Grid::parse(input);
+    let start = pipes.find(|&c| c == 'S').unwrap();
+
+    let connections = |c: char| -> &[Dir] {
+        match c {
+            '|' => &[Dir::North, Dir::South],
+            '-' => &[Dir::East, Dir::West],
+            'L' => &[Dir::North, Dir::East],
+            'J' => &[Dir::North, Dir::West],
+            '7' => &[Dir::South, Dir::West],
+            'F' => &[Dir::South, Dir::East],
+            'S' => &Dir::ALL,
+            _ => &[],
+        }
+    };
+
+    let successors = |&pos: &Pos| {
+        let pipes = &pipes;
+        connections(pipes[pos]).iter().filter_map(move |&dir| {
+            let next = pos.step(dir, 1);
+            let connected = connections(*pipes.get(next)?).contains(&dir.reverse());
+            connected.then_some(next)
+        })
+    };
+
+    let paths = search::bfs_all(start, successors);
+    paths.iter().map(|(_, steps)| steps).max().unwrap()
+}
+
+/// Day 17: the least heat lost, moving between `min` and `max` tiles before turning. It starts
+/// facing east, like the solution written by hand.
+fn day17_with_search(input: &str, min: u8, max: u8) -> u32 {
+    let city = Grid::parse_with(input, |c| c.to_digit(10).unwrap());
+    let end = Pos::new(city.width() as i64 - 1, city.height() as i64 - 1);
+
+    let successors = |&(pos, dir, straight): &(Pos, Dir, u8)| {
+        let city = &city;
+        let turns = [(dir.turn_left(), 1), (dir.turn_right(), 1)];
+        let turns = turns.into_iter().filter(move |_| straight >= min);
+        let ahead = (straight < max).then_some((dir, straight + 1));
+
+        turns.chain(ahead).filter_map(move |(dir, straight)| {
+            let next = pos.step(dir, 1);
+            city.get(next).map(|&heat| ((next, dir, straight), heat))
+        })
+    };
+
+    let is_goal = |&(pos, _, straight): &(Pos, Dir, u8)| pos == end && straight >= min;
+    let (_, heat) = search::dijkstra((Pos::ZERO, Dir::East, 0), successors, is_goal).unwrap();
+    heat
+}
+
+/// Day 23 part 2: the longest hike, ignoring the slopes.
+fn day23_with_search(input: &str) -> usize {
+    let maze = Grid::parse(input);
+    let start = Pos::new(1, 0);
+    let end = Pos::new(maze.width() as i64 - 2, maze.height() as i64 - 1);
+
+    let mut nodes = search::junctions(&maze, |&c| c != '#');
+    nodes.extend([start, end]);
+
+    let open = |&pos: &Pos| maze.neighbors4(pos).filter(|&next| maze[next] != '#');
+    let graph = search::compress(nodes, open);
+
+    let successors = |pos: &Pos| graph[pos].iter().copied();
+    search::longest_path(start, successors, |&pos| pos == end).unwrap()
+}
+
+/// A loop of pipes that snakes through every row of a `size` by `size` grid (which needs to be
+/// even) and comes back up the first column.
+fn pipe_loop(size: i64) -> String {
+    let mut path: Vec<Pos> = (0..size).map(|x| Pos::new(x, 0)).collect();
+    for y in 1..size {
+        let row = (1..size).map(|x| Pos::new(x, y));
+        match y % 2 {
+            1 => path.extend(row.rev()),
+            _ => path.extend(row),
+        }
+    }
+    path.extend((1..size).rev().map(|y| Pos::new(0, y)));
+
+    let dir_to = |from: Pos, to: Pos| Dir::ALL.into_iter().find(|&dir| from.step(dir, 1) == to);
+
+    let mut grid = Grid::new(size as usize, size as usize, '.');
+    for (i, &pos) in path.iter().enumerate() {
+        let previous = path[(i + path.len() - 1) % path.len()];
+        let next = path[(i + 1) % path.len()];
+
+        let mut dirs = [dir_to(pos, previous).unwrap(), dir_to(pos, next).unwrap()];
+        dirs.sort();
+        grid[pos] = match dirs {
+            [Dir::North, Dir::South] => '|',
+            [Dir::East, Dir::West] => '-',
+            [Dir::North, Dir::East] => 'L',
+            [Dir::North, Dir::West] => 'J',
+            [Dir::South, Dir::West] => '7',
+            [Dir::East, Dir::South] => 'F',
+            _ => unreachable!("Pipes connect two different directions"),
+        };
+    }
+    grid[Pos::ZERO] = 'S';
+
+    grid.to_string()
+}
+
+/// A city of `size` by `size` blocks with pseudo-random heat losses (with xorshift, so that it's
+/// the same on every run).
+fn city(size: usize) -> String {
+    let mut state = 0x2023_1217_u64;
+    let grid = Grid::from_fn(size, size, |_| {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        char::from_digit((state % 9 + 1) as u32, 10).unwrap()
+    });
+
+    grid.to_string()
+}
+
+/// A maze of `junctions` by `junctions` crossings connected by corridors of length `spacing`, with
+/// the entrance at the top left and the exit at the bottom right.
+fn maze(junctions: i64, spacing: i64) -> String {
+    let size = (junctions - 1) * spacing + 3;
+    let mut grid = Grid::new(size as usize, size as usize, '#');
+
+    for x in 0..junctions {
+        for y in 0..junctions {
+            let junction = Pos::new(1 + x * spacing, 1 + y * spacing);
+            if x + 1 < junctions {
+                (0..=spacing).for_each(|step| grid[junction.step(Dir::East, step)] = '.');
+            }
+            if y + 1 < junctions {
+                (0..=spacing).for_each(|step| grid[junction.step(Dir::South, step)] = '.');
+            }
+        }
+    }
+    grid[Pos::new(1, 0)] = '.';
+    grid[Pos::new(size - 2, size - 1)] = '.';
+
+    grid.to_string()
+}
+
+/// Benchmarks a solution written by hand against the one with `elvish::search`, after checking
+/// that they agree.
+fn compare(
+    c: &mut Criterion,
+    name: &str,
+    input: &str,
+    by_hand: fn(&[u8]) -> String,
+    with_search: impl Fn(&str) -> String,
+) {
+    let expected = by_hand(input.as_bytes());
+    assert_eq!(
+        with_search(input),
+        expected,
+        "{name} should give the same answer"
+    );
+
+    let mut group = c.benchmark_group(name);
+    group.sample_size(10);
+    group.bench_function("by hand", |b| {
+        b.iter(|| by_hand(black_box(input.as_bytes())))
+    });
+    group.bench_function("elvish::search", |b| {
+        b.iter(|| with_search(black_box(input)))
+    });
+    group.finish();
+}
+
+fn searches(c: &mut Criterion) {
+    let pipes = pipe_loop(140);
+    compare(
+        c,
+        "day10 part1",
+        &pipes,
+        run_day_part::<Solutions, 10, 1>,
+        |input| day10_with_search(input).to_string(),
+    );
+
+    let city = city(141);
+    compare(
+        c,
+        "day17 part1",
+        &city,
+        run_day_part::<Solutions, 17, 1>,
+        |input| day17_with_search(input, 0, 3).to_string(),
+    );
+    compare(
+        c,
+        "day17 part2",
+        &city,
+        run_day_part::<Solutions, 17, 2>,
+        |input| day17_with_search(input, 4, 10).to_string(),
+    );
+
+    let maze = maze(5, 20);
+    compare(
+        c,
+        "day23 part2",
+        &maze,
+        run_day_part::<Solutions, 23, 2>,
+        |input| day23_with_search(input).to_string(),
+    );
+}
+
+criterion_group!(benches, searches);
+criterion_main!(benches);
@@ -2,14 +2,17 @@
 //
 // ```rust
 // impl elvish::solution::Part<1, 1> for crate::Solutions {
-//     fn solve(input: &str) -> impl std::fmt::Display {
-//         part1(input)
+//     fn solve(input: &[u8]) -> impl std::fmt::Display {
+//         part1(<&str as elvish::solution::Input>::from_input(input))
 //     }
 // }
 //
 // #[test]
 // fn part1_example() {
-//     assert_eq!(part1(EXAMPLE_PART1), 142)
+//     assert_eq!(
+//         part1(<&str as elvish::solution::Input>::from_input(EXAMPLE_PART1.as_bytes())).to_string(),
+//         "142",
+//     )
 // }
 // ```
 #[elvish::solution(day = 1, example = 142)]
@@ -32,14 +35,17 @@ fn part1(input: &str) -> u32 {
 //
 // ```rust
 // impl elvish::solution::Part<2, 1> for crate::Solutions {
-//     fn solve(input: &str) -> impl std::fmt::Display {
-//         part2(input)
+//     fn solve(input: &[u8]) -> impl std::fmt::Display {
+//         part2(<&str as elvish::solution::Input>::from_input(input))
 //     }
 // }
 //
 // #[test]
 // fn part2_example() {
-//     assert_eq!(part2(EXAMPLE_PART2), 281)
+//     assert_eq!(
+//         part2(<&str as elvish::solution::Input>::from_input(EXAMPLE_PART2.as_bytes())).to_string(),
+//         "281",
+//     )
 // }
 // ```
 #[elvish::solution(day = 1, example = 281)]
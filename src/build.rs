@@ -0,0 +1,276 @@
+//! Helpers for build scripts, to select days with environment variables instead of cargo features.
+//!
+//! Calling [`configure`] from the crate's `build.rs` makes the macros gate days and parts on cfgs
+//! set by the build script, so the crate doesn't need to declare any features:
+//!
+//! ```no_run
+//! // In build.rs
+//!
+//! fn main() -> elvish::eyre::Result<()> {
+//!     elvish::build::configure()
+//! }
+//! ```
+//!
+//! Then, a single day can be compiled with `ELVISH_DAY=3 cargo run`. The supported environment
+//! variables are:
+//!
+//! - `ELVISH_DAY`: The days to compile, separated by commas (e.g. `3` or `1,2,5`), or ranges of
+//!   them (e.g. `1..=5`, or `1..5` without day 5). A single part of a day can be selected with
+//!   `day-part` (e.g. `3,4-2` for both parts of day 3 and part 2 of day 4, or `1..=5-1`). `today`
+//!   selects today's puzzle (see [`get_today`](crate::data::get_today)), which is only checked
+//!   when the build script runs, so it needs to be rerun for the next day (e.g. by touching
+//!   `build.rs`). All days are compiled if it's not set.
+//! - `ELVISH_PART`: The part to compile for the days that don't specify one, either `1` or `2`.
+//!   Both are compiled if it's not set.
+//! - `ELVISH_YEAR`: For crates with solutions for multiple years, the years to compile, separated
+//!   by commas (e.g. `2015,2016`). The selected days are compiled for each of them. All years are
+//!   compiled if it's not set.
+//! - `ELVISH_GENERATE_DOCS`: If set, solutions get the cached puzzle description as docs, like
+//!   with the `generate-docs` feature.
+
+use color_eyre::eyre;
+
+/// Configures the crate from its build script, emitting the cfgs for the selected days and parts.
+///
+/// See the [module docs](self) for the environment variables it reads.
+pub fn configure() -> eyre::Result<()> {
+    for var in [
+        "ELVISH_DAY",
+        "ELVISH_PART",
+        "ELVISH_YEAR",
+        "ELVISH_GENERATE_DOCS",
+        "ELVISH_TODAY",
+    ] {
+        println!("cargo::rerun-if-env-changed={var}");
+    }
+
+    // Tells the macros to use the cfgs below instead of features.
+    println!("cargo::rustc-env=ELVISH_CFG=1");
+
+    let days = (1..=25).map(|day| format!("\"{day}\""));
+    let parts = (1..=25).flat_map(|day| [1, 2].map(|part| format!("\"{day}-{part}\"")));
+    let days = days.collect::<Vec<_>>().join(", ");
+    let parts = parts.collect::<Vec<_>>().join(", ");
+    println!("cargo::rustc-check-cfg=cfg(elvish_day, values({days}))");
+    println!("cargo::rustc-check-cfg=cfg(elvish_part, values({parts}))");
+    println!("cargo::rustc-check-cfg=cfg(elvish_year, values(any()))");
+    println!("cargo::rustc-check-cfg=cfg(elvish_generate_docs)");
+
+    let today = crate::data::get_today();
+    let parts = parse_parts(env_var("ELVISH_DAY")?, env_var("ELVISH_PART")?, today)?;
+    let years = parse_years(env_var("ELVISH_YEAR")?)?;
+    let docs = std::env::var_os("ELVISH_GENERATE_DOCS").is_some();
+
+    for cfg in cfgs(&parts, &years, docs) {
+        println!("cargo::rustc-cfg={cfg}");
+    }
+
+    Ok(())
+}
+
+/// Gets an environment variable, if it's set.
+fn env_var(name: &str) -> eyre::Result<Option<String>> {
+    match std::env::var(name) {
+        Ok(value) => Ok(Some(value)),
+        Err(std::env::VarError::NotPresent) => Ok(None),
+        Err(err) => eyre::bail!("`{name}` should be valid unicode: {err}"),
+    }
+}
+
+/// Gets the cfgs to set for the selected parts (sorted by day) and years.
+fn cfgs(parts: &[(u8, u8)], years: &[String], docs: bool) -> Vec<String> {
+    let mut cfgs = Vec::new();
+
+    let mut last_day = None;
+    for &(day, part) in parts {
+        if last_day != Some(day) {
+            cfgs.push(format!("elvish_day=\"{day}\""));
+            last_day = Some(day);
+        }
+
+        cfgs.push(format!("elvish_part=\"{day}-{part}\""));
+    }
+
+    for year in years {
+        cfgs.push(format!("elvish_year=\"{year}\""));
+    }
+
+    if docs {
+        cfgs.push("elvish_generate_docs".to_string());
+    }
+
+    cfgs
+}
+
+/// Parses the selected years (from `ELVISH_YEAR`), or gets `all` if they aren't selected.
+fn parse_years(years: Option<String>) -> eyre::Result<Vec<String>> {
+    let Some(years) = years else {
+        return Ok(vec!["all".to_string()]);
+    };
+
+    years
+        .split(',')
+        .map(|year| match year.trim().parse::<i16>() {
+            Ok(year @ 2015..) => Ok(year.to_string()),
+            _ => eyre::bail!("`ELVISH_YEAR` should be years since 2015, was {year:?}"),
+        })
+        .collect()
+}
+
+/// Parses the selected days (from `ELVISH_DAY`) and the default part (from `ELVISH_PART`) into the
+/// selected parts of each day, sorted and without duplicates.
+fn parse_parts(
+    days: Option<String>,
+    part: Option<String>,
+    today: Option<u8>,
+) -> eyre::Result<Vec<(u8, u8)>> {
+    let default_parts = match part.as_deref() {
+        None => vec![1, 2],
+        Some("1") => vec![1],
+        Some("2") => vec![2],
+        Some(other) => eyre::bail!("`ELVISH_PART` needs to be either 1 or 2, was {other:?}"),
+    };
+
+    let with_default_parts = |day: u8| default_parts.iter().map(move |&part| (day, part));
+
+    let Some(days) = days else {
+        return Ok((1..=25).flat_map(with_default_parts).collect());
+    };
+
+    let mut selected = Vec::new();
+    for entry in days.split(',') {
+        let (days, part) = match entry.trim().split_once('-') {
+            Some((days, part)) => (days, Some(part)),
+            None => (entry.trim(), None),
+        };
+
+        let days = match (days.trim(), today) {
+            ("today", Some(today)) => today..=today,
+            ("today", None) => eyre::bail!(
+                "There is no puzzle today, so `ELVISH_DAY` can't be `today`. Set the \
+                `ELVISH_TODAY` environment variable to choose a day"
+            ),
+            (days, _) => match parse_day_range(days) {
+                Some(days) => days,
+                None => eyre::bail!(
+                    "`ELVISH_DAY` should be days between 1 and 25 (or ranges), was {entry:?}"
+                ),
+            },
+        };
+
+        for day in days {
+            match part {
+                None => selected.extend(with_default_parts(day)),
+                Some("1") => selected.push((day, 1)),
+                Some("2") => selected.push((day, 2)),
+                Some(part) => {
+                    eyre::bail!("Part needs to be either 1 or 2, was {part:?} in {entry:?}")
+                }
+            }
+        }
+    }
+
+    selected.sort();
+    selected.dedup();
+
+    Ok(selected)
+}
+
+/// Parses a day (like `3`) or a range of days (like `1..=5` or `1..5`), which can't be empty.
+fn parse_day_range(days: &str) -> Option<std::ops::RangeInclusive<u8>> {
+    let day = |day: &str| match day.trim().parse() {
+        Ok(day @ 1..=25) => Some(day),
+        _ => None,
+    };
+
+    let range = match days.split_once("..") {
+        None => day(days)?..=day(days)?,
+        Some((start, end)) => match end.strip_prefix('=') {
+            Some(end) => day(start)?..=day(end)?,
+            // The end can be one past the last day.
+            None => day(start)?..=end.trim().parse::<u8>().ok()?.checked_sub(1)?,
+        },
+    };
+
+    (!range.is_empty() && *range.end() <= 25).then_some(range)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parts(days: Option<&str>, part: Option<&str>) -> eyre::Result<Vec<(u8, u8)>> {
+        parse_parts(days.map(String::from), part.map(String::from), None)
+    }
+
+    #[test]
+    fn days_and_parts() {
+        let selected = parts(Some("3,4-2"), None).unwrap();
+        assert_eq!(selected, [(3, 1), (3, 2), (4, 2)]);
+
+        let selected = parts(Some(" 5 , 1-1,5-2 "), Some("1")).unwrap();
+        assert_eq!(selected, [(1, 1), (5, 1), (5, 2)]);
+    }
+
+    #[test]
+    fn all_days_by_default() {
+        let all: Vec<_> = (1..=25).flat_map(|day| [(day, 1), (day, 2)]).collect();
+        assert_eq!(parts(None, None).unwrap(), all);
+
+        let part2: Vec<_> = (1..=25).map(|day| (day, 2)).collect();
+        assert_eq!(parts(None, Some("2")).unwrap(), part2);
+    }
+
+    #[test]
+    fn today() {
+        let today = |days: &str| parse_parts(Some(days.to_string()), None, Some(7));
+        assert_eq!(today("today").unwrap(), [(7, 1), (7, 2)]);
+        assert_eq!(today("3, today-2").unwrap(), [(3, 1), (3, 2), (7, 2)]);
+
+        assert!(parts(Some("today"), None).is_err());
+    }
+
+    #[test]
+    fn ranges() {
+        let selected = parts(Some("1..=3"), Some("1")).unwrap();
+        assert_eq!(selected, [(1, 1), (2, 1), (3, 1)]);
+
+        let selected = parts(Some("23..26-2,2..3"), None).unwrap();
+        assert_eq!(selected, [(2, 1), (2, 2), (23, 2), (24, 2), (25, 2)]);
+    }
+
+    #[test]
+    fn invalid_input() {
+        let invalid = [
+            "0", "26", "three", "3-3", "3-", "", "5..5", "4..=3", "1..=26", "..3",
+        ];
+        for days in invalid {
+            let selected = parts(Some(days), None);
+            assert!(selected.is_err(), "{days:?} should be invalid");
+        }
+
+        assert!(parts(None, Some("3")).is_err());
+        assert!(parse_years(Some("2014".to_string())).is_err());
+    }
+
+    #[test]
+    fn emitted_cfgs() {
+        let years = parse_years(Some("2015, 2016".to_string())).unwrap();
+        assert_eq!(
+            cfgs(&[(3, 1), (3, 2), (4, 2)], &years, true),
+            [
+                r#"elvish_day="3""#,
+                r#"elvish_part="3-1""#,
+                r#"elvish_part="3-2""#,
+                r#"elvish_day="4""#,
+                r#"elvish_part="4-2""#,
+                r#"elvish_year="2015""#,
+                r#"elvish_year="2016""#,
+                "elvish_generate_docs",
+            ]
+        );
+
+        let years = parse_years(None).unwrap();
+        assert_eq!(cfgs(&[], &years, false), [r#"elvish_year="all""#]);
+    }
+}
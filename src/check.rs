@@ -0,0 +1,110 @@
+//! Property-based testing of solutions, by checking that they agree with another solution (e.g. a
+//! brute force one) on random inputs.
+//!
+//! Needs the `check` feature. Usually it's used through the `check_against` and `gen` arguments of
+//! [`solution`](crate::solution), which generate a test calling [`check_against`]:
+//!
+//! ```ignore
+//! use elvish::check::proptest::prelude::*;
+//!
+//! #[elvish::solution(day = 9, check_against = brute_force, gen = gen_input)]
+//! fn part1(input: &str) -> i64 {
+//!     // --snip--
+//! }
+//!
+//! fn brute_force(input: &str) -> i64 {
+//!     // --snip--
+//! }
+//!
+//! fn gen_input() -> impl Strategy<Value = String> {
+//!     prop::collection::vec(-100i64..100, 1..20).prop_map(|numbers| {
+//!         numbers.iter().map(|n| format!("{n}\n")).collect()
+//!     })
+//! }
+//! ```
+//!
+//! Inputs are generated with [`proptest`] strategies (which is re-exported, so it doesn't need to
+//! be added as a dependency). Regexes are strategies too, so simple inputs can be generated with
+//! something like `"([0-9]{1,3}\n){1,10}"`.
+
+pub use proptest;
+
+use std::fmt::Display;
+use std::path::PathBuf;
+
+use proptest::strategy::Strategy;
+use proptest::test_runner::{Config, TestCaseError, TestError, TestRunner};
+
+/// The directory where minimal failing inputs get written.
+const FAILURES_PATH: &str = ".elvish/failures";
+
+/// Checks that `solution` and `reference` give the same output (as displayed) on random inputs.
+///
+/// If they don't, the input is shrunk to a minimal one where they still disagree, which is written
+/// to `.elvish/failures/{name}.txt` (so it can be run with `--input`), and this panics.
+///
+/// The number of inputs can be changed with the `PROPTEST_CASES` environment variable.
+///
+/// # Example
+///
+/// ```
+/// fn sum_digits(input: &[u8]) -> u32 {
+///     input.iter().map(|b| u32::from(b - b'0')).sum()
+/// }
+///
+/// fn brute_force(input: &[u8]) -> u32 {
+///     let number: String = String::from_utf8(input.to_vec()).unwrap();
+///     number.chars().map(|c| c.to_digit(10).unwrap()).sum()
+/// }
+///
+/// elvish::check::check_against("sum_digits", "[0-9]{0,20}", sum_digits, brute_force);
+/// ```
+pub fn check_against<A: Display, B: Display>(
+    name: &str,
+    inputs: impl Strategy<Value = String>,
+    solution: impl Fn(&[u8]) -> A,
+    reference: impl Fn(&[u8]) -> B,
+) {
+    let config = Config {
+        // Failing inputs are written to `.elvish` instead.
+        failure_persistence: None,
+        ..Config::default()
+    };
+
+    let result = TestRunner::new(config).run(&inputs, |input| {
+        let expected = reference(input.as_bytes()).to_string();
+        let output = solution(input.as_bytes()).to_string();
+
+        if output == expected {
+            Ok(())
+        } else {
+            Err(TestCaseError::fail(format!(
+                "Solution gave {output:?}, but the reference gave {expected:?}"
+            )))
+        }
+    });
+
+    match result {
+        Ok(()) => (),
+        Err(TestError::Abort(reason)) => panic!("Checking `{name}` was aborted: {reason}"),
+        Err(TestError::Fail(reason, input)) => {
+            let saved = match write_failure(name, &input) {
+                Ok(path) => format!("Saved to {}", path.display()),
+                Err(err) => format!("Couldn't save it: {err}"),
+            };
+
+            panic!("{reason}. Minimal failing input for `{name}` ({saved}):\n{input}");
+        }
+    }
+}
+
+fn write_failure(name: &str, input: &str) -> std::io::Result<PathBuf> {
+    // Names are usually paths, like `crate::day09::part1`.
+    let file_name = name.replace("::", "-");
+    let path = PathBuf::from(FAILURES_PATH).join(format!("{file_name}.txt"));
+
+    std::fs::create_dir_all(FAILURES_PATH)?;
+    std::fs::write(&path, input)?;
+
+    Ok(path)
+}
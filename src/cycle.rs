@@ -0,0 +1,132 @@
+//! Cycle detection, for puzzles that ask for the state after simulating a billion steps.
+//!
+//! States are generated from a start with a `step` function. Since puzzles have finitely many
+//! states, the sequence eventually repeats: after a prefix of `prefix_len` states, it cycles
+//! through `cycle_len` states forever. Every function here returns `(prefix_len, cycle_len)`, and
+//! loops forever if the sequence never repeats.
+//!
+//! # Example
+//!
+//! ```
+//! use elvish::cycle;
+//!
+//! // 1, 2, 4, 8, 6, 2, 4, 8, 6, ...
+//! let step = |&x: &u64| (x * 2) % 10;
+//!
+//! assert_eq!(cycle::find(1, step), (1, 4));
+//! assert_eq!(cycle::floyd(1, step), (1, 4));
+//! assert_eq!(cycle::brent(1, step), (1, 4));
+//!
+//! assert_eq!(cycle::nth(1, step, 1_000_000_000), 6);
+//! ```
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Finds the cycle by remembering every state, which takes `prefix_len + cycle_len` steps but
+/// keeps all those states in memory.
+pub fn find<S: Clone + Eq + Hash>(start: S, step: impl FnMut(&S) -> S) -> (usize, usize) {
+    let (prefix_len, history) = history(start, step);
+    (prefix_len, history.len() - prefix_len)
+}
+
+/// Gets the state after `n` steps, without simulating them all (see [`find`]).
+pub fn nth<S: Clone + Eq + Hash>(start: S, step: impl FnMut(&S) -> S, n: usize) -> S {
+    let (prefix_len, mut history) = history(start, step);
+
+    let index = if n < prefix_len {
+        n
+    } else {
+        let cycle_len = history.len() - prefix_len;
+        prefix_len + (n - prefix_len) % cycle_len
+    };
+
+    history.swap_remove(index)
+}
+
+/// Simulates until a state repeats, returning the length of the prefix and every state up to the
+/// repeated one.
+fn history<S: Clone + Eq + Hash>(start: S, mut step: impl FnMut(&S) -> S) -> (usize, Vec<S>) {
+    let mut seen = HashMap::new();
+    let mut history = Vec::new();
+    let mut state = start;
+
+    loop {
+        if let Some(&prefix_len) = seen.get(&state) {
+            return (prefix_len, history);
+        }
+
+        seen.insert(state.clone(), history.len());
+        let next = step(&state);
+        history.push(state);
+        state = next;
+    }
+}
+
+/// Finds the cycle with Floyd's tortoise and hare algorithm, which only keeps two states in memory
+/// (and doesn't need them to be hashable), at the cost of taking about three times as many steps
+/// as [`find`].
+pub fn floyd<S: Clone + Eq>(start: S, mut step: impl FnMut(&S) -> S) -> (usize, usize) {
+    let mut tortoise = step(&start);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        hare = step(&hare);
+    }
+
+    // The distance between them is now a multiple of the cycle length, so moving both at the same
+    // speed from here and from the start makes them meet at the start of the cycle.
+    let mut prefix_len = 0;
+    tortoise = start;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        prefix_len += 1;
+    }
+
+    let mut cycle_len = 1;
+    hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        cycle_len += 1;
+    }
+
+    (prefix_len, cycle_len)
+}
+
+/// Finds the cycle with Brent's algorithm, which only keeps two states in memory like [`floyd`],
+/// but usually takes fewer steps.
+pub fn brent<S: Clone + Eq>(start: S, mut step: impl FnMut(&S) -> S) -> (usize, usize) {
+    // Find the cycle length, by moving the tortoise to the hare at every power of two.
+    let mut power = 1;
+    let mut cycle_len = 1;
+    let mut tortoise = start.clone();
+    let mut hare = step(&start);
+    while tortoise != hare {
+        if power == cycle_len {
+            tortoise = hare.clone();
+            power *= 2;
+            cycle_len = 0;
+        }
+
+        hare = step(&hare);
+        cycle_len += 1;
+    }
+
+    // With the hare a cycle ahead, they meet at the start of the cycle.
+    let mut tortoise = start.clone();
+    let mut hare = start;
+    for _ in 0..cycle_len {
+        hare = step(&hare);
+    }
+
+    let mut prefix_len = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        prefix_len += 1;
+    }
+
+    (prefix_len, cycle_len)
+}
@@ -0,0 +1,492 @@
+//! Explicit graphs of named nodes, for puzzles that list each node's neighbors (like
+//! `name: neighbors`), with connectivity algorithms.
+//!
+//! Nodes are interned: each name gets an index (in order of appearance), and the algorithms work
+//! on indices. Use [`Graph::name`] to get back the names.
+//!
+//! For graphs that are only given by a `successors` function, like positions in a maze, see
+//! [`search`](crate::search) instead.
+//!
+//! # Example
+//!
+//! ```
+//! use elvish::graph::Graph;
+//!
+//! // Components wired together, like in 2023 day 25.
+//! let wiring = Graph::parse_undirected("
+//! a: b c d
+//! b: c d
+//! c: d
+//! d: e
+//! e: f g
+//! f: g h
+//! g: h
+//! ");
+//!
+//! let (cut, side) = wiring.min_cut().unwrap();
+//! assert_eq!(cut, 1);
+//! assert_eq!(side.len() * (wiring.len() - side.len()), 16);
+//! ```
+
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+
+/// A graph of nodes named by `N`, which is either directed or undirected.
+#[derive(Debug, Clone)]
+pub struct Graph<N> {
+    directed: bool,
+    names: Vec<N>,
+    indices: HashMap<N, usize>,
+    neighbors: Vec<Vec<usize>>,
+}
+
+impl<'a> Graph<&'a str> {
+    /// Parses a directed graph, with a line per node followed by its neighbors, like `a: b c` or
+    /// `a -> b, c`. Neighbors are separated by spaces or commas, and nodes without neighbors can be
+    /// on a line of their own.
+    ///
+    /// ```
+    /// use elvish::graph::Graph;
+    ///
+    /// let graph = Graph::parse("broadcaster -> a, b\na -> b\nb -> output");
+    /// assert_eq!(graph.len(), 4);
+    /// assert_eq!(graph.neighbors(0).len(), 2);
+    /// ```
+    pub fn parse(input: &'a str) -> Self {
+        Self::parse_lines(input, Self::directed())
+    }
+
+    /// Parses an undirected graph, like [`parse`](Self::parse). Each edge only needs to be listed
+    /// once, on the line of either of its nodes, and is only added once if it's on both.
+    pub fn parse_undirected(input: &'a str) -> Self {
+        Self::parse_lines(input, Self::undirected())
+    }
+
+    fn parse_lines(input: &'a str, mut graph: Self) -> Self {
+        for line in input.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            let (name, neighbors) = line
+                .split_once("->")
+                .or_else(|| line.split_once(':'))
+                .unwrap_or((line, ""));
+
+            let node = graph.add_node(name.trim());
+            for neighbor in neighbors.split([' ', ',']).filter(|n| !n.is_empty()) {
+                let neighbor = graph.add_node(neighbor);
+                if graph.directed || !graph.neighbors[node].contains(&neighbor) {
+                    graph.add_edge_between(node, neighbor);
+                }
+            }
+        }
+
+        graph
+    }
+}
+
+impl<N: Clone + Eq + Hash> Graph<N> {
+    /// Creates an empty directed graph.
+    pub fn directed() -> Self {
+        Self {
+            directed: true,
+            names: Vec::new(),
+            indices: HashMap::new(),
+            neighbors: Vec::new(),
+        }
+    }
+
+    /// Creates an empty undirected graph.
+    pub fn undirected() -> Self {
+        Self {
+            directed: false,
+            ..Self::directed()
+        }
+    }
+
+    /// Whether edges only go one way.
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    /// The number of nodes.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Checks if there are no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Gets the name of a node.
+    pub fn name(&self, node: usize) -> &N {
+        &self.names[node]
+    }
+
+    /// Gets the index of a node from its name.
+    pub fn index_of(&self, name: &N) -> Option<usize> {
+        self.indices.get(name).copied()
+    }
+
+    /// Iterates over the indices of the nodes.
+    pub fn nodes(&self) -> std::ops::Range<usize> {
+        0..self.len()
+    }
+
+    /// Gets the nodes that a node has edges to.
+    pub fn neighbors(&self, node: usize) -> &[usize] {
+        &self.neighbors[node]
+    }
+
+    /// Iterates over the edges, as `(from, to)`. Edges of undirected graphs are only returned
+    /// once, from the node with the lowest index.
+    pub fn edges(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.neighbors
+            .iter()
+            .enumerate()
+            .flat_map(|(from, neighbors)| neighbors.iter().map(move |&to| (from, to)))
+            .filter(|&(from, to)| self.directed || from <= to)
+    }
+
+    /// Adds a node (if it doesn't exist yet), returning its index.
+    pub fn add_node(&mut self, name: N) -> usize {
+        if let Some(&index) = self.indices.get(&name) {
+            return index;
+        }
+
+        let index = self.names.len();
+        self.names.push(name.clone());
+        self.indices.insert(name, index);
+        self.neighbors.push(Vec::new());
+        index
+    }
+
+    /// Adds an edge between two nodes, adding the nodes if needed. Returns their indices.
+    pub fn add_edge(&mut self, from: N, to: N) -> (usize, usize) {
+        let (from, to) = (self.add_node(from), self.add_node(to));
+        self.add_edge_between(from, to);
+        (from, to)
+    }
+
+    fn add_edge_between(&mut self, from: usize, to: usize) {
+        self.neighbors[from].push(to);
+        if !self.directed && from != to {
+            self.neighbors[to].push(from);
+        }
+    }
+
+    /// Finds the connected components, ignoring the direction of edges. Each component is sorted,
+    /// and they're sorted by their first node.
+    ///
+    /// ```
+    /// use elvish::graph::Graph;
+    ///
+    /// let graph = Graph::parse_undirected("a: b\nc: d e\nf");
+    /// assert_eq!(graph.components(), [vec![0, 1], vec![2, 3, 4], vec![5]]);
+    /// ```
+    pub fn components(&self) -> Vec<Vec<usize>> {
+        let mut sets = UnionFind::new(self.len());
+        for (from, to) in self.edges() {
+            sets.union(from, to);
+        }
+
+        let mut components: Vec<Vec<usize>> = Vec::new();
+        let mut component_of = HashMap::new();
+        for node in self.nodes() {
+            let root = sets.find(node);
+            let index = *component_of.entry(root).or_insert_with(|| {
+                components.push(Vec::new());
+                components.len() - 1
+            });
+            components[index].push(node);
+        }
+
+        components
+    }
+
+    /// Sorts the nodes so that edges only go forward, or returns `None` if there is a cycle. Edges
+    /// of undirected graphs go both ways, so they don't have an order: this returns `None` for them.
+    ///
+    /// ```
+    /// use elvish::graph::Graph;
+    ///
+    /// let graph = Graph::parse("shirt: tie belt\ntie: jacket\nbelt: jacket\njacket");
+    /// let order = graph.topological_sort().unwrap();
+    /// let names: Vec<_> = order.iter().map(|&node| *graph.name(node)).collect();
+    /// assert_eq!(names, ["shirt", "tie", "belt", "jacket"]);
+    ///
+    /// assert_eq!(Graph::parse("a: b\nb: a").topological_sort(), None);
+    /// assert_eq!(Graph::parse_undirected("a: b").topological_sort(), None);
+    /// ```
+    pub fn topological_sort(&self) -> Option<Vec<usize>> {
+        if !self.directed {
+            return None;
+        }
+
+        let mut incoming: Vec<usize> = vec![0; self.len()];
+        for (_, to) in self.edges() {
+            incoming[to] += 1;
+        }
+
+        let mut queue: VecDeque<usize> = self.nodes().filter(|&n| incoming[n] == 0).collect();
+        let mut order = Vec::with_capacity(self.len());
+        while let Some(node) = queue.pop_front() {
+            order.push(node);
+            for &next in &self.neighbors[node] {
+                incoming[next] -= 1;
+                if incoming[next] == 0 {
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        (order.len() == self.len()).then_some(order)
+    }
+
+    /// Finds the strongly connected components (where every node can reach every other one),
+    /// with Tarjan's algorithm. They're in reverse topological order: edges between components
+    /// only go to earlier ones.
+    ///
+    /// ```
+    /// use elvish::graph::Graph;
+    ///
+    /// let graph = Graph::parse("a: b\nb: c\nc: a d\nd: e\ne: d");
+    /// assert_eq!(graph.strongly_connected_components(), [vec![3, 4], vec![0, 1, 2]]);
+    /// ```
+    pub fn strongly_connected_components(&self) -> Vec<Vec<usize>> {
+        let mut order = vec![None; self.len()];
+        let mut low = vec![0; self.len()];
+        let mut on_stack = vec![false; self.len()];
+        let mut stack = Vec::new();
+        let mut components = Vec::new();
+        let mut visited = 0;
+
+        for root in self.nodes() {
+            if order[root].is_some() {
+                continue;
+            }
+
+            // The nodes being visited, with the index of their next neighbor to visit. This
+            // avoids recursion, which would overflow the stack on long paths.
+            let mut path = vec![(root, 0)];
+            order[root] = Some(visited);
+            low[root] = visited;
+            visited += 1;
+            stack.push(root);
+            on_stack[root] = true;
+
+            while let Some(&(node, next)) = path.last() {
+                if let Some(&neighbor) = self.neighbors[node].get(next) {
+                    path.last_mut().unwrap().1 += 1;
+
+                    match order[neighbor] {
+                        None => {
+                            order[neighbor] = Some(visited);
+                            low[neighbor] = visited;
+                            visited += 1;
+                            stack.push(neighbor);
+                            on_stack[neighbor] = true;
+                            path.push((neighbor, 0));
+                        }
+                        Some(order) if on_stack[neighbor] => low[node] = low[node].min(order),
+                        Some(_) => (),
+                    }
+
+                    continue;
+                }
+
+                path.pop();
+                if let Some(&(parent, _)) = path.last() {
+                    low[parent] = low[parent].min(low[node]);
+                }
+
+                if Some(low[node]) == order[node] {
+                    let start = stack.iter().rposition(|&n| n == node).unwrap();
+                    let mut component = stack.split_off(start);
+                    for &member in &component {
+                        on_stack[member] = false;
+                    }
+                    component.sort_unstable();
+                    components.push(component);
+                }
+            }
+        }
+
+        components
+    }
+
+    /// Finds a minimum cut, ignoring the direction of edges, with the Stoer–Wagner algorithm.
+    /// Returns how many edges are cut, and the nodes on one side of it. Returns `None` if there
+    /// are fewer than two nodes.
+    pub fn min_cut(&self) -> Option<(usize, Vec<usize>)> {
+        if self.len() < 2 {
+            return None;
+        }
+
+        // Nodes get merged together, so they're weighted by how many edges they stand for.
+        let mut weights: Vec<HashMap<usize, usize>> = vec![HashMap::new(); self.len()];
+        for (from, to) in self.edges().filter(|&(from, to)| from != to) {
+            *weights[from].entry(to).or_default() += 1;
+            *weights[to].entry(from).or_default() += 1;
+        }
+
+        let mut members: Vec<Vec<usize>> = self.nodes().map(|node| vec![node]).collect();
+        let mut remaining: Vec<usize> = self.nodes().collect();
+        let mut best: Option<(usize, Vec<usize>)> = None;
+
+        while remaining.len() > 1 {
+            // Adds nodes in order of how connected they are to the ones already added.
+            let mut connection = vec![0; self.len()];
+            let mut added = vec![false; self.len()];
+            let mut queue = BinaryHeap::from([(0, remaining[0])]);
+            let mut order = Vec::with_capacity(remaining.len());
+
+            while let Some((weight, node)) = queue.pop() {
+                if added[node] || weight != connection[node] {
+                    continue;
+                }
+
+                added[node] = true;
+                order.push((node, weight));
+                for (&neighbor, &weight) in &weights[node] {
+                    if !added[neighbor] {
+                        connection[neighbor] += weight;
+                        queue.push((connection[neighbor], neighbor));
+                    }
+                }
+            }
+
+            if order.len() < remaining.len() {
+                // The graph isn't connected, so there's nothing to cut.
+                let side = order.iter().flat_map(|&(node, _)| &members[node]);
+                return Some((0, side.copied().collect()));
+            }
+
+            // The last node is only cut from the rest by the edges connecting it.
+            let [.., (kept, _), (last, cut)] = order[..] else {
+                unreachable!("There should be at least two nodes left")
+            };
+            if best.as_ref().is_none_or(|&(best, _)| cut < best) {
+                best = Some((cut, members[last].clone()));
+            }
+
+            // Merges the last two nodes together.
+            let merged = std::mem::take(&mut members[last]);
+            members[kept].extend(merged);
+            for (neighbor, weight) in std::mem::take(&mut weights[last]) {
+                weights[neighbor].remove(&last);
+                if neighbor != kept {
+                    *weights[kept].entry(neighbor).or_default() += weight;
+                    *weights[neighbor].entry(kept).or_default() += weight;
+                }
+            }
+            remaining.retain(|&node| node != last);
+        }
+
+        best
+    }
+}
+
+/// Disjoint sets of `0..len`, which can be merged, with union-find.
+///
+/// ```
+/// use elvish::graph::UnionFind;
+///
+/// let mut sets = UnionFind::new(4);
+/// sets.union(0, 1);
+/// sets.union(2, 3);
+/// assert!(sets.same(1, 0));
+/// assert!(!sets.same(1, 2));
+/// assert_eq!(sets.count(), 2);
+/// assert_eq!(sets.size(3), 2);
+/// ```
+#[derive(Debug, Clone)]
+pub struct UnionFind {
+    parents: Vec<usize>,
+    sizes: Vec<usize>,
+    count: usize,
+}
+
+impl UnionFind {
+    /// Creates `len` sets, each with a single element.
+    pub fn new(len: usize) -> Self {
+        Self {
+            parents: (0..len).collect(),
+            sizes: vec![1; len],
+            count: len,
+        }
+    }
+
+    /// Gets the representative of the set containing an element.
+    pub fn find(&mut self, mut element: usize) -> usize {
+        while self.parents[element] != element {
+            // Path halving, so that later lookups are quicker.
+            self.parents[element] = self.parents[self.parents[element]];
+            element = self.parents[element];
+        }
+        element
+    }
+
+    /// Merges the sets containing two elements. Returns `false` if they were already the same.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+
+        if self.sizes[a] < self.sizes[b] {
+            (a, b) = (b, a);
+        }
+        self.parents[b] = a;
+        self.sizes[a] += self.sizes[b];
+        self.count -= 1;
+        true
+    }
+
+    /// Checks if two elements are in the same set.
+    pub fn same(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Gets the size of the set containing an element.
+    pub fn size(&mut self, element: usize) -> usize {
+        let root = self.find(element);
+        self.sizes[root]
+    }
+
+    /// The number of sets.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn undirected_graphs_have_no_topological_order() {
+        assert_eq!(Graph::parse_undirected("a: b").topological_sort(), None);
+        assert_eq!(Graph::parse_undirected("a\nb").topological_sort(), None);
+    }
+
+    #[test]
+    fn undirected_edges_listed_twice_are_added_once() {
+        let graph = Graph::parse_undirected("a: b c\nb: a\nc: a");
+        assert_eq!(graph.neighbors(0), [1, 2]);
+        assert_eq!(graph.neighbors(1), [0]);
+        assert_eq!(graph.edges().count(), 2);
+    }
+
+    #[test]
+    fn min_cut_counts_edges_listed_twice_once() {
+        let graph = Graph::parse_undirected("a: b c\nb: a c\nc: a b d\nd: c");
+        let (cut, side) = graph.min_cut().unwrap();
+        assert_eq!(cut, 1);
+        assert!(side == [3] || side == [0, 1, 2]);
+    }
+
+    #[test]
+    fn directed_edges_listed_twice_are_kept() {
+        let graph = Graph::parse("a: b b");
+        assert_eq!(graph.neighbors(0), [1, 1]);
+    }
+}
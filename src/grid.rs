@@ -0,0 +1,518 @@
+//! Dense grids, for puzzles with character maps.
+//!
+//! # Example
+//!
+//! ```
+//! use elvish::grid::{Dir, Grid, Pos};
+//!
+//! let grid = Grid::parse("
+//! .#.
+//! ..#
+//! #..
+//! ".trim());
+//!
+//! assert_eq!(grid.width(), 3);
+//! assert_eq!(grid[Pos::new(0, 2)], '#');
+//!
+//! let start = Pos::new(1, 1);
+//! let open: Vec<_> = grid.neighbors4(start).filter(|&pos| grid[pos] == '.').collect();
+//! assert_eq!(open, [Pos::new(1, 2), Pos::new(0, 1)]);
+//!
+//! assert_eq!(start + Dir::East, Pos::new(2, 1));
+//! assert_eq!(grid.transpose().to_string(), "..#\n#..\n.#.");
+//! ```
+
+use std::fmt::{self, Display};
+use std::ops::{Add, AddAssign, Index, IndexMut, Mul, Neg, Sub, SubAssign};
+
+/// A position in a grid. `x` grows to the right (east) and `y` downwards (south), like in puzzle
+/// texts.
+///
+/// Coordinates are signed, so that positions outside of a grid can be represented (e.g. when
+/// stepping off an edge, or in infinitely tiling maps).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Pos {
+    /// The column.
+    pub x: i64,
+
+    /// The row.
+    pub y: i64,
+}
+
+impl Pos {
+    /// The top left corner of a grid.
+    pub const ZERO: Pos = Pos::new(0, 0);
+
+    /// Creates a position from its column and row.
+    pub const fn new(x: i64, y: i64) -> Self {
+        Self { x, y }
+    }
+
+    /// Moves `steps` tiles in a direction.
+    pub fn step(self, dir: Dir, steps: i64) -> Self {
+        self + dir.delta() * steps
+    }
+
+    /// The manhattan (taxicab) distance between two positions.
+    pub fn manhattan(self, other: Pos) -> u64 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    /// The positions directly above, to the right, below and to the left (in that order).
+    pub fn neighbors4(self) -> impl Iterator<Item = Pos> {
+        Dir::ALL.into_iter().map(move |dir| self + dir)
+    }
+
+    /// The positions around this one, including diagonals (clockwise, starting from the top left).
+    pub fn neighbors8(self) -> impl Iterator<Item = Pos> {
+        const DELTAS: [Pos; 8] = [
+            Pos::new(-1, -1),
+            Pos::new(0, -1),
+            Pos::new(1, -1),
+            Pos::new(1, 0),
+            Pos::new(1, 1),
+            Pos::new(0, 1),
+            Pos::new(-1, 1),
+            Pos::new(-1, 0),
+        ];
+
+        DELTAS.into_iter().map(move |delta| self + delta)
+    }
+}
+
+impl Add for Pos {
+    type Output = Pos;
+
+    fn add(self, rhs: Pos) -> Pos {
+        Pos::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl Sub for Pos {
+    type Output = Pos;
+
+    fn sub(self, rhs: Pos) -> Pos {
+        Pos::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl Mul<i64> for Pos {
+    type Output = Pos;
+
+    fn mul(self, rhs: i64) -> Pos {
+        Pos::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl Neg for Pos {
+    type Output = Pos;
+
+    fn neg(self) -> Pos {
+        Pos::new(-self.x, -self.y)
+    }
+}
+
+impl Add<Dir> for Pos {
+    type Output = Pos;
+
+    fn add(self, rhs: Dir) -> Pos {
+        self + rhs.delta()
+    }
+}
+
+impl AddAssign for Pos {
+    fn add_assign(&mut self, rhs: Pos) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for Pos {
+    fn sub_assign(&mut self, rhs: Pos) {
+        *self = *self - rhs;
+    }
+}
+
+impl AddAssign<Dir> for Pos {
+    fn add_assign(&mut self, rhs: Dir) {
+        *self = *self + rhs;
+    }
+}
+
+/// One of the four cardinal directions.
+///
+/// ```
+/// use elvish::grid::Dir;
+///
+/// assert_eq!(Dir::North.turn_right(), Dir::East);
+/// assert_eq!(Dir::North.turn_left(), Dir::West);
+/// assert_eq!(Dir::try_from('v'), Ok(Dir::South));
+/// assert_eq!(Dir::try_from('L'), Ok(Dir::West));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Dir {
+    /// Up, towards negative `y`.
+    North,
+    /// Right, towards positive `x`.
+    East,
+    /// Down, towards positive `y`.
+    South,
+    /// Left, towards negative `x`.
+    West,
+}
+
+impl Dir {
+    /// All directions, clockwise starting from north.
+    pub const ALL: [Dir; 4] = [Dir::North, Dir::East, Dir::South, Dir::West];
+
+    /// How a position changes when moving one tile in this direction.
+    pub const fn delta(self) -> Pos {
+        match self {
+            Dir::North => Pos::new(0, -1),
+            Dir::East => Pos::new(1, 0),
+            Dir::South => Pos::new(0, 1),
+            Dir::West => Pos::new(-1, 0),
+        }
+    }
+
+    /// Rotates 90 degrees clockwise.
+    pub const fn turn_right(self) -> Dir {
+        match self {
+            Dir::North => Dir::East,
+            Dir::East => Dir::South,
+            Dir::South => Dir::West,
+            Dir::West => Dir::North,
+        }
+    }
+
+    /// Rotates 90 degrees counterclockwise.
+    pub const fn turn_left(self) -> Dir {
+        self.reverse().turn_right()
+    }
+
+    /// The opposite direction.
+    pub const fn reverse(self) -> Dir {
+        match self {
+            Dir::North => Dir::South,
+            Dir::East => Dir::West,
+            Dir::South => Dir::North,
+            Dir::West => Dir::East,
+        }
+    }
+
+    /// Whether the direction is north or south.
+    pub const fn is_vertical(self) -> bool {
+        matches!(self, Dir::North | Dir::South)
+    }
+}
+
+/// Parses arrows (`^>v<`), the initials of up/right/down/left (`URDL`) and of the cardinal
+/// directions (`NESW`).
+impl TryFrom<char> for Dir {
+    type Error = char;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            '^' | 'U' | 'N' => Ok(Dir::North),
+            '>' | 'R' | 'E' => Ok(Dir::East),
+            'v' | 'D' | 'S' => Ok(Dir::South),
+            '<' | 'L' | 'W' => Ok(Dir::West),
+            other => Err(other),
+        }
+    }
+}
+
+/// A dense, rectangular grid, stored row by row.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl Grid<char> {
+    /// Parses a grid of characters, one row per line. Empty lines at the start and end are
+    /// ignored.
+    ///
+    /// # Panics
+    ///
+    /// If the rows don't all have the same width.
+    pub fn parse(input: &str) -> Self {
+        Self::parse_with(input, |c| c)
+    }
+}
+
+impl<T> Grid<T> {
+    /// Creates a grid with every cell set to `value`.
+    pub fn new(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// Creates a grid with the value of each cell given by a function of its position.
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(Pos) -> T) -> Self {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| Pos::new(x as i64, y as i64)))
+            .map(&mut f)
+            .collect();
+
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Parses a grid, one row per line, converting each character with `f`. Empty lines at the
+    /// start and end are ignored.
+    ///
+    /// ```
+    /// use elvish::grid::Grid;
+    ///
+    /// let grid = Grid::parse_with("123\n456\n", |c| c.to_digit(10).unwrap());
+    /// assert_eq!(grid.row(1), [4, 5, 6]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the rows don't all have the same width.
+    pub fn parse_with(input: &str, mut f: impl FnMut(char) -> T) -> Self {
+        let lines = input.trim_matches(['\n', '\r']).lines();
+
+        let mut width = None;
+        let mut height = 0;
+        let mut cells = Vec::new();
+        for (y, line) in lines.enumerate() {
+            let len_before = cells.len();
+            cells.extend(line.chars().map(&mut f));
+
+            let row_width = cells.len() - len_before;
+            let width = *width.get_or_insert(row_width);
+            assert_eq!(
+                width, row_width,
+                "Grid rows should all have the same width, but row {y} has a different one"
+            );
+
+            height += 1;
+        }
+
+        Self {
+            width: width.unwrap_or(0),
+            height,
+            cells,
+        }
+    }
+
+    /// The number of columns.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// The number of rows.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Whether a position is inside the grid.
+    pub fn in_bounds(&self, pos: Pos) -> bool {
+        (0..self.width as i64).contains(&pos.x) && (0..self.height as i64).contains(&pos.y)
+    }
+
+    fn index_of(&self, pos: Pos) -> Option<usize> {
+        self.in_bounds(pos)
+            .then(|| pos.y as usize * self.width + pos.x as usize)
+    }
+
+    /// Gets the value at a position, if it is inside the grid.
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        self.index_of(pos).map(|i| &self.cells[i])
+    }
+
+    /// Gets the value at a position mutably, if it is inside the grid.
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        self.index_of(pos).map(|i| &mut self.cells[i])
+    }
+
+    /// Gets the value at a position, as if the grid tiled the plane infinitely.
+    ///
+    /// ```
+    /// use elvish::grid::{Grid, Pos};
+    ///
+    /// let grid = Grid::parse("ab\ncd");
+    /// assert_eq!(grid.get_wrapping(Pos::new(-1, 5)), &'d');
+    /// ```
+    pub fn get_wrapping(&self, pos: Pos) -> &T {
+        &self[self.wrap(pos)]
+    }
+
+    /// Maps a position to the equivalent one inside the grid, as if the grid tiled the plane.
+    pub fn wrap(&self, pos: Pos) -> Pos {
+        Pos::new(
+            pos.x.rem_euclid(self.width as i64),
+            pos.y.rem_euclid(self.height as i64),
+        )
+    }
+
+    /// All positions of the grid, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| Pos::new(x as i64, y as i64)))
+    }
+
+    /// All cells with their positions, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(&self.cells)
+    }
+
+    /// All cells with their positions mutably, row by row.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Pos, &mut T)> {
+        self.positions().zip(&mut self.cells)
+    }
+
+    /// Finds the position of the first cell (row by row) that satisfies a predicate.
+    pub fn find(&self, mut predicate: impl FnMut(&T) -> bool) -> Option<Pos> {
+        self.iter()
+            .find(|(_, value)| predicate(value))
+            .map(|(pos, _)| pos)
+    }
+
+    /// The neighbors of a position (see [`Pos::neighbors4`]) which are inside the grid.
+    pub fn neighbors4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        pos.neighbors4().filter(|&pos| self.in_bounds(pos))
+    }
+
+    /// The neighbors of a position, including diagonals (see [`Pos::neighbors8`]), which are
+    /// inside the grid.
+    pub fn neighbors8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        pos.neighbors8().filter(|&pos| self.in_bounds(pos))
+    }
+
+    /// A row of the grid.
+    ///
+    /// # Panics
+    ///
+    /// If the row is out of bounds.
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    /// A row of the grid, mutably.
+    ///
+    /// # Panics
+    ///
+    /// If the row is out of bounds.
+    pub fn row_mut(&mut self, y: usize) -> &mut [T] {
+        &mut self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    /// All rows, from top to bottom.
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // `chunks` panics with a size of 0.
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// A column of the grid, from top to bottom.
+    ///
+    /// # Panics
+    ///
+    /// If the column is out of bounds.
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "Column {x} is out of bounds");
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    /// All columns, from left to right.
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|x| self.column(x))
+    }
+
+    /// Converts every cell with a function.
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// Creates a grid with the positions transformed by `f`, which maps positions of the new grid
+    /// to positions of this one.
+    fn remap(&self, width: usize, height: usize, f: impl Fn(i64, i64) -> Pos) -> Self
+    where
+        T: Clone,
+    {
+        Grid::from_fn(width, height, |pos| self[f(pos.x, pos.y)].clone())
+    }
+
+    /// Swaps rows and columns (mirroring along the main diagonal).
+    pub fn transpose(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.remap(self.height, self.width, |x, y| Pos::new(y, x))
+    }
+
+    /// Rotates the grid 90 degrees clockwise.
+    ///
+    /// ```
+    /// use elvish::grid::Grid;
+    ///
+    /// let grid = Grid::parse("ab\ncd\nef");
+    /// assert_eq!(grid.rotate_right().to_string(), "eca\nfdb");
+    /// assert_eq!(grid.rotate_left().to_string(), "bdf\nace");
+    /// ```
+    pub fn rotate_right(&self) -> Self
+    where
+        T: Clone,
+    {
+        let height = self.height as i64;
+        self.remap(self.height, self.width, |x, y| Pos::new(y, height - 1 - x))
+    }
+
+    /// Rotates the grid 90 degrees counterclockwise.
+    pub fn rotate_left(&self) -> Self
+    where
+        T: Clone,
+    {
+        let width = self.width as i64;
+        self.remap(self.height, self.width, |x, y| Pos::new(width - 1 - y, x))
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        self.get(pos)
+            .unwrap_or_else(|| panic!("Position {pos:?} is out of the bounds of the grid"))
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("Position {pos:?} is out of the bounds of the grid"))
+    }
+}
+
+/// Renders the grid back like in puzzle texts, one row per line (without a trailing newline).
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+
+            for value in row {
+                write!(f, "{value}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -13,6 +13,7 @@
 //! - Simple and consice syntax to write out 90% of required tests
 //! - See the puzzle description as docs on the annotated function
 //! - Conditional compilation to compile a single day
+//! - Helpers for common puzzles, like [grids](grid) and [searches](search)
 
 #![warn(missing_docs)]
 
@@ -20,21 +21,68 @@ pub use elvish_core::*;
 pub use elvish_macros as macros;
 
 pub use color_eyre::eyre;
-pub use elvish_macros::{available_days, example, solution};
+pub use elvish_macros::{available_days, example, solution, solutions_type};
 pub use indoc::indoc;
 
+pub mod build;
+#[cfg(feature = "check")]
+pub mod check;
+pub mod cycle;
+pub mod graph;
+pub mod grid;
+pub mod linalg;
+pub mod math;
+pub mod parse;
+pub mod polygon;
+pub mod ranges;
+pub mod search;
+pub mod snapshot;
+
 /// Convinience for declaring 25-sets with feature flags at once.
 pub mod declare {
     pub use elvish_macros::declare_modules as modules;
     pub use elvish_macros::declare_run_fn as run_fn;
 }
 
+/// Wraps the tests generated by `check_against` in [`solution`], so that they fail to compile with
+/// a clear error if the `check` feature isn't enabled.
+#[doc(hidden)]
+#[cfg(feature = "check")]
+#[macro_export]
+macro_rules! check_test {
+    ($($test:tt)*) => {
+        $($test)*
+    };
+}
+
+#[doc(hidden)]
+#[cfg(not(feature = "check"))]
+#[macro_export]
+macro_rules! check_test {
+    ($($test:tt)*) => {
+        ::std::compile_error!(
+            "`check_against` needs the `check` feature of elvish (usually enabled in \
+             `[dev-dependencies]`)"
+        );
+    };
+}
+
+use std::path::{Path, PathBuf};
+
 use clap::Parser;
 
 #[derive(Parser)]
 struct CliArgs {
     day: Option<u8>,
     part: Option<u8>,
+
+    /// The year to run, if there are solutions for more than one.
+    #[arg(long)]
+    year: Option<i16>,
+
+    /// Use the contents of this file as input instead of the puzzle input.
+    #[arg(long)]
+    input: Option<PathBuf>,
 }
 
 /// Copy the output of the solution to the clipboard.
@@ -44,9 +92,26 @@ pub fn copy_to_clipboard(input: &str) -> eyre::Result<()> {
     Ok(())
 }
 
+/// Memory-maps a file, so that big inputs don't need to be read into memory up front.
+fn map_file(path: &Path) -> eyre::Result<memmap2::Mmap> {
+    let file = std::fs::File::open(path)?;
+
+    // SAFETY: The map is only read from, and input files aren't expected to be modified while a
+    // solution is running.
+    let map = unsafe { memmap2::Mmap::map(&file)? };
+
+    Ok(map)
+}
+
 /// Runs elvish. 
 ///
-/// This gives a cli program that can take a day, or auto detect it.
+/// This gives a cli program that can take a day, or auto detect it: if only one day is available
+/// (like with the `today` feature) it runs that one, and otherwise today's puzzle if it's compiled
+/// (see [`data::get_today`]).
+///
+/// The puzzle input is fetched and cached the first time, and then memory-mapped from the cache
+/// (or from the file given with `--input`), so that it doesn't need to be read into memory up
+/// front.
 ///
 /// The two arguments needed can be obtained by using the provided macros
 /// [`available_days!()`] and [`declare::run_fn`].
@@ -72,16 +137,110 @@ pub fn copy_to_clipboard(input: &str) -> eyre::Result<()> {
 /// ```
 pub fn run<const YEAR: i16>(
     available_days: &[u8],
-    run_day_part: impl Fn(u8, u8, &str) -> eyre::Result<String>,
+    run_day_part: impl Fn(u8, u8, &[u8]) -> eyre::Result<String>,
 ) -> eyre::Result<()> {
+    run_years(&[Year::new(YEAR, available_days, &run_day_part)])
+}
+
+/// Function that runs a part of a day (as generated by [`declare::run_fn`]).
+pub type RunDayPart<'a> = &'a dyn Fn(u8, u8, &[u8]) -> eyre::Result<String>;
+
+/// The solutions for a year of advent of code, to run with [`run_years`].
+pub struct Year<'a> {
+    year: i16,
+    available_days: &'a [u8],
+    run_day_part: RunDayPart<'a>,
+}
+
+impl<'a> Year<'a> {
+    /// Declares the solutions for a year. The arguments are the same ones [`run`] takes, but
+    /// obtained from the module with that year's solutions.
+    pub fn new(year: i16, available_days: &'a [u8], run_day_part: RunDayPart<'a>) -> Self {
+        Self {
+            year,
+            available_days,
+            run_day_part,
+        }
+    }
+}
+
+/// Runs elvish with solutions for multiple years.
+///
+/// Same as [`run`], but the year can be selected with `--year`. If it isn't passed, the `YEAR`
+/// environment variable is used.
+///
+/// # Example
+///
+/// ```no_run
+/// // In main.rs
+///
+/// use color_eyre::eyre;
+///
+/// mod year2015 {
+///     // Solutions are registered with `#[elvish::solution(year = 2015, day = X)]`
+///     pub struct Solutions;
+///
+///     elvish::declare::run_fn!(2015);
+/// }
+///
+/// mod year2016 {
+///     pub struct Solutions;
+///
+///     elvish::declare::run_fn!(2016);
+/// }
+///
+/// fn main() -> eyre::Result<()> {
+///     dotenvy::dotenv()?;
+///
+///     elvish::run_years(&[
+///         elvish::Year::new(2015, &elvish::available_days!(2015), &year2015::run_day_part),
+///         elvish::Year::new(2016, &elvish::available_days!(2016), &year2016::run_day_part),
+///     ])
+/// }
+/// ```
+pub fn run_years(years: &[Year]) -> eyre::Result<()> {
     let args = CliArgs::parse();
+    let available_years: Vec<_> = years.iter().map(|year| year.year).collect();
+
+    let year = match (years, args.year) {
+        ([], _) => eyre::bail!("There are no years to run"),
 
+        ([year], arg) => {
+            if let Some(arg) = arg {
+                if arg != year.year {
+                    tracing::warn!("Ignoring year argument given (year {arg}), since the only runnable year is {}", year.year)
+                }
+            }
+
+            year
+        }
+
+        (_, arg) => {
+            let Some(arg) = arg.or_else(|| crate::data::get_env_year().ok()) else {
+                eyre::bail!(
+                    "Please pass a year to run with `--year`. Available years are: {available_years:?}"
+                );
+            };
+
+            let Some(year) = years.iter().find(|year| year.year == arg) else {
+                eyre::bail!("Year {arg} is not runnable. Available years are: {available_years:?}");
+            };
+
+            year
+        }
+    };
+
+    let available_days = year.available_days;
     let day = match (available_days, args.day) {
         (&[day], arg) => {
             if let Some(arg) = arg {
                 if arg != day {
                     tracing::warn!("Ignoring day argument given (day {arg}), since the only runnable day is {day}")
                 }
+            } else if let Some(today) = crate::data::get_today().filter(|&today| today != day) {
+                // Today's day is picked while compiling with the `today` feature, and cargo
+                // doesn't know to recompile when the date changes.
+                tracing::warn!("Running day {day}, the only runnable day, but today's puzzle is day {today}. With the `today` feature, touch a source file to recompile for the new day")
             }
 
             day
@@ -95,16 +254,25 @@ pub fn run<const YEAR: i16>(
             arg
         }
 
-        (_, None) => eyre::bail!(
-            "Please pass a day to run with `--day`. Available days are: {available_days:?}"
-        ),
+        (_, None) => match crate::data::get_today() {
+            Some(today) if available_days.contains(&today) => today,
+            _ => eyre::bail!(
+                "Please pass a day to run with `--day`. Available days are: {available_days:?}"
+            ),
+        },
     };
 
-    let session_token = crate::data::get_session_token()?;
-    let input = crate::data::get(YEAR, day, &session_token)?.input;
+    let input = match &args.input {
+        Some(path) => map_file(path)?,
+        None => {
+            let session_token = crate::data::get_session_token()?;
+            let path = crate::data::get_input_path(year.year, day, &session_token)?;
+            map_file(&path)?
+        }
+    };
 
     let run_part = |part| -> eyre::Result<()> {
-        let output = run_day_part(day, part, &input)?;
+        let output = (year.run_day_part)(day, part, &input)?;
         println!(
             "Solution for day {day} part {part} is: {output}",
             part = part + 1
@@ -114,7 +282,8 @@ pub fn run<const YEAR: i16>(
     };
 
     match args.part {
-        Some(part) => run_part(part - 1)?,
+        Some(part @ (1 | 2)) => run_part(part - 1)?,
+        Some(part) => eyre::bail!("Part needs to be either 1 or 2, was {part}"),
         None => {
             run_part(0)?;
             run_part(1)?;
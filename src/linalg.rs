@@ -0,0 +1,369 @@
+//! Exact linear algebra on [`Rational`]s, for puzzles that reduce to small systems of equations or
+//! to intersecting lines.
+//!
+//! Solving with floats needs a tolerance that depends on the input, while fractions give exact
+//! results. Eliminations are computed on big integers, so only the results need to fit in a
+//! [`Rational`] (e.g. systems with coefficients around `10^14` work).
+//!
+//! # Example
+//!
+//! ```
+//! use elvish::linalg::{Line, Matrix};
+//! use elvish::math::Rational;
+//!
+//! // 2x + y = 5 and x - 3y = -1
+//! let matrix = Matrix::new([[2, 1], [1, -3]]);
+//! let solution = matrix.solve(&[5, -1]).unwrap();
+//! assert_eq!(solution, [Rational::from(2), Rational::from(1)]);
+//!
+//! // Hailstones' paths crossing, like in 2023 day 24.
+//! let a = Line::new([19, 13], [-2, 1]);
+//! let b = Line::new([18, 19], [-1, -1]);
+//! let crossing = a.intersection(&b).unwrap();
+//! assert_eq!(crossing.point, [Rational::new(43, 3), Rational::new(46, 3)]);
+//! assert!(crossing.time > Rational::ZERO && crossing.other_time > Rational::ZERO);
+//! ```
+
+use std::fmt::{self, Display};
+use std::ops::{Index, IndexMut, Mul};
+
+use num_bigint::BigInt;
+
+use crate::math::{lcm_all, Rational};
+
+/// A dense matrix of [`Rational`]s.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Matrix {
+    rows: usize,
+    columns: usize,
+    entries: Vec<Rational>,
+}
+
+impl Matrix {
+    /// Creates a matrix from its rows.
+    ///
+    /// # Panics
+    ///
+    /// If the rows don't all have the same length.
+    pub fn new(
+        rows: impl IntoIterator<Item = impl IntoIterator<Item = impl Into<Rational>>>,
+    ) -> Self {
+        let mut matrix = Self::zeros(0, 0);
+
+        for (i, row) in rows.into_iter().enumerate() {
+            matrix.entries.extend(row.into_iter().map(Into::into));
+            if i == 0 {
+                matrix.columns = matrix.entries.len();
+            }
+            matrix.rows += 1;
+
+            assert_eq!(
+                matrix.entries.len(),
+                matrix.rows * matrix.columns,
+                "Row {i} should have {} columns like the first one",
+                matrix.columns
+            );
+        }
+
+        matrix
+    }
+
+    /// Creates a matrix filled with 0.
+    pub fn zeros(rows: usize, columns: usize) -> Self {
+        Self {
+            rows,
+            columns,
+            entries: vec![Rational::ZERO; rows * columns],
+        }
+    }
+
+    /// Creates the identity matrix of a size.
+    pub fn identity(size: usize) -> Self {
+        let mut matrix = Self::zeros(size, size);
+        for i in 0..size {
+            matrix[(i, i)] = Rational::ONE;
+        }
+        matrix
+    }
+
+    /// The number of rows.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// The number of columns.
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    /// Gets a row.
+    pub fn row(&self, row: usize) -> &[Rational] {
+        &self.entries[row * self.columns..(row + 1) * self.columns]
+    }
+
+    /// Gets the determinant.
+    ///
+    /// # Panics
+    ///
+    /// If the matrix isn't square.
+    ///
+    /// ```
+    /// use elvish::linalg::Matrix;
+    /// use elvish::math::Rational;
+    ///
+    /// let matrix = Matrix::new([[2, 0, 1], [1, 3, 2], [1, 1, 2]]);
+    /// assert_eq!(matrix.determinant(), Rational::from(6));
+    /// assert_eq!(Matrix::new([[1, 2], [2, 4]]).determinant(), Rational::ZERO);
+    /// ```
+    pub fn determinant(&self) -> Rational {
+        self.gauss_jordan(&Self::zeros(self.rows, 0))
+            .map_or(Rational::ZERO, |((numerator, denominator), _)| {
+                to_rational(numerator, denominator)
+            })
+    }
+
+    /// Gets the inverse, or `None` if the matrix is singular.
+    ///
+    /// # Panics
+    ///
+    /// If the matrix isn't square.
+    ///
+    /// ```
+    /// use elvish::linalg::Matrix;
+    ///
+    /// let matrix = Matrix::new([[2, 0, 1], [1, 3, 2], [1, 1, 2]]);
+    /// let inverse = matrix.inverse().unwrap();
+    /// assert_eq!(&matrix * &inverse, Matrix::identity(3));
+    /// assert_eq!(Matrix::new([[1, 2], [2, 4]]).inverse(), None);
+    /// ```
+    pub fn inverse(&self) -> Option<Self> {
+        let (_, inverse) = self.gauss_jordan(&Self::identity(self.rows))?;
+        Some(inverse)
+    }
+
+    /// Solves the system `self * x = rhs` for `x`, or returns `None` if it doesn't have exactly one
+    /// solution (i.e. if the matrix is singular).
+    ///
+    /// # Panics
+    ///
+    /// If the matrix isn't square, or if `rhs` doesn't have a value for each row.
+    pub fn solve<T: Into<Rational> + Copy>(&self, rhs: &[T]) -> Option<Vec<Rational>> {
+        assert_eq!(rhs.len(), self.rows, "There should be a value for each row");
+
+        let (_, solution) = self.gauss_jordan(&Self::new(rhs.iter().map(|&value| [value])))?;
+        Some(solution.entries)
+    }
+
+    /// Reduces the matrix to the identity with Gauss-Jordan elimination, applying the same row
+    /// operations to `other`. Returns the determinant (as a fraction, since it's often too big for
+    /// a [`Rational`] even when the results aren't) and the transformed `other` (which is
+    /// `self⁻¹ * other`), or `None` if the matrix is singular.
+    fn gauss_jordan(&self, other: &Self) -> Option<((BigInt, BigInt), Self)> {
+        assert_eq!(self.rows, self.columns, "Matrix should be square");
+        let size = self.rows;
+
+        // Scales the rows to integers, which scales the determinant by the same factor.
+        let mut scale = BigInt::from(1);
+        let mut rows: Vec<Vec<BigInt>> = (0..size)
+            .map(|row| {
+                let entries = || self.row(row).iter().chain(other.row(row));
+                let factor = lcm_all(entries().map(|entry| entry.denominator()));
+                scale *= factor;
+
+                entries()
+                    .map(|entry| BigInt::from(entry.numerator()) * (factor / entry.denominator()))
+                    .collect()
+            })
+            .collect();
+
+        // Fraction-free elimination (Bareiss' algorithm): the entries stay integers, which are
+        // minors of the matrix, instead of fractions with huge intermediate values. The division
+        // by the previous pivot is always exact.
+        let mut previous = BigInt::from(1);
+        let mut negated = false;
+
+        for column in 0..size {
+            let pivot = (column..size).find(|&row| rows[row][column] != BigInt::ZERO)?;
+            if pivot != column {
+                rows.swap(pivot, column);
+                negated = !negated;
+            }
+
+            let pivot_row = rows[column].clone();
+            for (_, row) in rows.iter_mut().enumerate().filter(|&(i, _)| i != column) {
+                let factor = std::mem::take(&mut row[column]);
+                for (j, entry) in row.iter_mut().enumerate().filter(|&(j, _)| j != column) {
+                    *entry = (&pivot_row[column] * &*entry - &factor * &pivot_row[j]) / &previous;
+                }
+            }
+
+            previous = pivot_row[column].clone();
+        }
+
+        // Every row now has `previous` (the determinant of the scaled matrix) on the diagonal.
+        let determinant = if negated {
+            (-previous.clone(), scale)
+        } else {
+            (previous.clone(), scale)
+        };
+
+        let mut result = Self::zeros(size, other.columns);
+        for (row, entries) in rows.into_iter().enumerate() {
+            for (column, entry) in entries.into_iter().skip(size).enumerate() {
+                result[(row, column)] = to_rational(entry, previous.clone());
+            }
+        }
+
+        Some((determinant, result))
+    }
+}
+
+/// Reduces a fraction of big integers to a [`Rational`].
+///
+/// # Panics
+///
+/// If it doesn't fit.
+fn to_rational(numerator: BigInt, denominator: BigInt) -> Rational {
+    let (mut a, mut b) = (numerator.clone(), denominator.clone());
+    while b != BigInt::ZERO {
+        (a, b) = (b.clone(), a % b);
+    }
+
+    let narrow = |n: BigInt| i128::try_from(n).expect("Result should fit in a Rational");
+    Rational::new(narrow(numerator / &a), narrow(denominator / &a))
+}
+
+/// Gets the entry at `(row, column)`.
+impl Index<(usize, usize)> for Matrix {
+    type Output = Rational;
+
+    fn index(&self, (row, column): (usize, usize)) -> &Rational {
+        assert!(column < self.columns, "Column {column} is out of bounds");
+        &self.entries[row * self.columns + column]
+    }
+}
+
+impl IndexMut<(usize, usize)> for Matrix {
+    fn index_mut(&mut self, (row, column): (usize, usize)) -> &mut Rational {
+        assert!(column < self.columns, "Column {column} is out of bounds");
+        &mut self.entries[row * self.columns + column]
+    }
+}
+
+impl Mul for &Matrix {
+    type Output = Matrix;
+
+    fn mul(self, other: &Matrix) -> Matrix {
+        assert_eq!(
+            self.columns, other.rows,
+            "Matrices should have compatible sizes"
+        );
+
+        let mut product = Matrix::zeros(self.rows, other.columns);
+        for row in 0..self.rows {
+            for column in 0..other.columns {
+                product[(row, column)] = (0..self.columns)
+                    .map(|i| self[(row, i)] * other[(i, column)])
+                    .fold(Rational::ZERO, |sum, value| sum + value);
+            }
+        }
+        product
+    }
+}
+
+/// Displays the matrix with a row per line, and columns separated by spaces.
+impl Display for Matrix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in 0..self.rows {
+            if row > 0 {
+                writeln!(f)?;
+            }
+
+            let entries: Vec<_> = self.row(row).iter().map(Rational::to_string).collect();
+            write!(f, "{}", entries.join(" "))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A line in `N` dimensions, going through `point` in the direction of `direction`: its points
+/// are `point + t * direction`.
+///
+/// With `t` as time, it's also the path of something moving at constant velocity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Line<const N: usize> {
+    /// A point on the line (the position at `t = 0`).
+    pub point: [i64; N],
+    /// The direction of the line (the velocity).
+    pub direction: [i64; N],
+}
+
+/// Where two [`Line`]s intersect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Intersection<const N: usize> {
+    /// The intersection.
+    pub point: [Rational; N],
+    /// The `t` at which the first line reaches the intersection.
+    pub time: Rational,
+    /// The `t` at which the second line reaches the intersection.
+    pub other_time: Rational,
+}
+
+impl<const N: usize> Line<N> {
+    /// Creates a line going through `point` in the direction of `direction`.
+    pub fn new(point: [i64; N], direction: [i64; N]) -> Self {
+        Self { point, direction }
+    }
+
+    /// Gets the point `point + t * direction`.
+    pub fn at(&self, t: impl Into<Rational>) -> [Rational; N] {
+        let t = t.into();
+        std::array::from_fn(|axis| t * self.direction[axis] + self.point[axis])
+    }
+
+    /// Finds where two lines intersect. Returns `None` if they don't (in 3D and more they can
+    /// miss each other), or if they are parallel (even if they are the same line).
+    ///
+    /// ```
+    /// use elvish::linalg::Line;
+    /// use elvish::math::Rational;
+    ///
+    /// let a = Line::new([0, 0, 0], [1, 1, 1]);
+    /// let b = Line::new([4, 0, 2], [-1, 1, 0]);
+    /// let crossing = a.intersection(&b).unwrap();
+    /// assert_eq!(crossing.point, [2, 2, 2].map(Rational::from));
+    ///
+    /// let c = Line::new([4, 0, 3], [-1, 1, 0]);
+    /// assert_eq!(a.intersection(&c), None);
+    /// ```
+    pub fn intersection(&self, other: &Self) -> Option<Intersection<N>> {
+        // Solves `self.point + t * self.direction = other.point + u * other.direction` on two
+        // axes where the directions aren't parallel, and checks that it works on all of them.
+        let (a, b) = (self.direction, other.direction);
+        let cross = |i: usize, j: usize| {
+            i128::from(a[i]) * i128::from(b[j]) - i128::from(a[j]) * i128::from(b[i])
+        };
+        let (i, j) = (0..N)
+            .flat_map(|i| (i + 1..N).map(move |j| (i, j)))
+            .find(|&(i, j)| cross(i, j) != 0)?;
+
+        let d = |axis: usize| i128::from(other.point[axis]) - i128::from(self.point[axis]);
+        let determinant = cross(i, j);
+        let time = Rational::new(
+            d(i) * i128::from(b[j]) - d(j) * i128::from(b[i]),
+            determinant,
+        );
+        let other_time = Rational::new(
+            d(i) * i128::from(a[j]) - d(j) * i128::from(a[i]),
+            determinant,
+        );
+
+        let point = self.at(time);
+        (point == other.at(other_time)).then_some(Intersection {
+            point,
+            time,
+            other_time,
+        })
+    }
+}
@@ -0,0 +1,415 @@
+//! Number theory helpers, for puzzles where several cycles have to line up.
+//!
+//! # Example
+//!
+//! ```
+//! use elvish::math;
+//!
+//! // Ghosts that reach an end every 3, 4 and 10 steps all do at once after:
+//! assert_eq!(math::lcm_all([3_u64, 4, 10]), 60);
+//!
+//! // A disc at position 2 of 5 and one at position 1 of 3, turning every second.
+//! // They're both at position 0 after `t` seconds when `t = -2 (mod 5)` and `t = -1 (mod 3)`.
+//! assert_eq!(math::crt([(-2, 5), (-1, 3)]), Some((8, 15)));
+//! ```
+
+use std::ops::{Div, Rem};
+
+pub mod poly;
+mod rational;
+
+pub use rational::Rational;
+
+/// The primitive integer types, which the generic helpers like [`gcd`] and [`lcm`] work on.
+pub trait Integer: Copy + Ord + Div<Output = Self> + Rem<Output = Self> {
+    /// Zero.
+    const ZERO: Self;
+    /// One.
+    const ONE: Self;
+
+    /// The absolute value (the value itself for unsigned types).
+    ///
+    /// # Panics
+    ///
+    /// Like the primitive one, if it overflows (for `T::MIN` of signed types).
+    fn abs(self) -> Self;
+
+    /// The absolute value, returning `None` on overflow.
+    fn checked_abs(self) -> Option<Self>;
+
+    /// Multiplies, returning `None` on overflow.
+    fn checked_mul(self, other: Self) -> Option<Self>;
+}
+
+macro_rules! impl_integer {
+    ($abs:ident: $($t:ty),*) => {$(
+        impl Integer for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            fn abs(self) -> Self {
+                impl_integer!(@$abs self)
+            }
+
+            fn checked_abs(self) -> Option<Self> {
+                impl_integer!(@checked $abs self)
+            }
+
+            fn checked_mul(self, other: Self) -> Option<Self> {
+                <$t>::checked_mul(self, other)
+            }
+        }
+    )*};
+    (@signed $value:ident) => { $value.abs() };
+    (@unsigned $value:ident) => { $value };
+    (@checked signed $value:ident) => { $value.checked_abs() };
+    (@checked unsigned $value:ident) => { Some($value) };
+}
+
+impl_integer!(signed: i8, i16, i32, i64, i128, isize);
+impl_integer!(unsigned: u8, u16, u32, u64, u128, usize);
+
+/// Gets the greatest common divisor of two numbers, which is never negative (and is 0 only if
+/// both numbers are 0).
+///
+/// # Panics
+///
+/// If the result doesn't fit in the type, which only happens for signed types when one number is
+/// `T::MIN` and the other one is either 0 or `T::MIN`.
+///
+/// ```
+/// # use elvish::math::gcd;
+/// assert_eq!(gcd(12, 18), 6);
+/// assert_eq!(gcd(-4, 6), 2);
+/// assert_eq!(gcd(0, 5), 5);
+/// assert_eq!(gcd(i64::MIN, 6), 2);
+/// ```
+pub fn gcd<T: Integer>(a: T, b: T) -> T {
+    // `T::MIN` has no absolute value, so it's reduced by the other number first (the remainder is
+    // smaller than it, so it has one).
+    let (mut a, mut b) = match (a.checked_abs(), b.checked_abs()) {
+        (Some(a), Some(b)) => (a, b),
+        (None, Some(other)) if other != T::ZERO => (other, (a % other).abs()),
+        (Some(other), None) if other != T::ZERO => (other, (b % other).abs()),
+        _ => panic!("GCD should fit in its type"),
+    };
+    while b != T::ZERO {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Gets the least common multiple of two numbers, which is never negative (and is 0 if either
+/// number is 0).
+///
+/// # Panics
+///
+/// If the result overflows.
+///
+/// ```
+/// # use elvish::math::lcm;
+/// assert_eq!(lcm(4, 6), 12);
+/// assert_eq!(lcm(-3, 5), 15);
+/// assert_eq!(lcm(i64::MIN / 2, 2), 1 << 62);
+/// ```
+pub fn lcm<T: Integer>(a: T, b: T) -> T {
+    if a == T::ZERO || b == T::ZERO {
+        return T::ZERO;
+    }
+
+    // Divides before taking the absolute value, which might not exist otherwise.
+    let gcd = gcd(a, b);
+    (a / gcd)
+        .checked_abs()
+        .zip(b.checked_abs())
+        .and_then(|(a, b)| a.checked_mul(b))
+        .expect("LCM should fit in its type")
+}
+
+/// Gets the greatest common divisor of all the numbers, or 0 if there are none.
+pub fn gcd_all<T: Integer>(numbers: impl IntoIterator<Item = T>) -> T {
+    numbers.into_iter().fold(T::ZERO, gcd)
+}
+
+/// Gets the least common multiple of all the numbers, or 1 if there are none.
+///
+/// # Panics
+///
+/// If the result overflows.
+pub fn lcm_all<T: Integer>(numbers: impl IntoIterator<Item = T>) -> T {
+    numbers.into_iter().fold(T::ONE, lcm)
+}
+
+/// Gets `(gcd, x, y)` such that `a * x + b * y = gcd`, with the extended Euclidean algorithm.
+///
+/// # Panics
+///
+/// If the GCD doesn't fit in an `i64`, like [`gcd`] (the coefficients always do).
+///
+/// ```
+/// # use elvish::math::extended_gcd;
+/// let (gcd, x, y) = extended_gcd(240, 46);
+/// assert_eq!(gcd, 2);
+/// assert_eq!(240 * x + 46 * y, 2);
+/// ```
+pub fn extended_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    let (gcd, x, y) = extended_gcd_wide(a.into(), b.into());
+    let narrow = |n: i128| i64::try_from(n).expect("Extended GCD should fit in i64");
+
+    (narrow(gcd), narrow(x), narrow(y))
+}
+
+/// [`extended_gcd`] on `i128`, so that callers can keep intermediate values from overflowing.
+fn extended_gcd_wide(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_x, x) = (x, old_x - quotient * x);
+        (old_y, y) = (y, old_y - quotient * y);
+    }
+
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// Solves a system of congruences `x = residue (mod modulus)` with the Chinese remainder theorem,
+/// returning `(x, modulus)` such that all solutions are `x (mod modulus)`, with `0 <= x < modulus`.
+///
+/// The moduli don't need to be coprime. Returns `None` if there are no solutions, or if the
+/// combined modulus (the LCM of all the moduli) doesn't fit in an `i64`.
+///
+/// # Panics
+///
+/// If a modulus isn't positive.
+///
+/// ```
+/// # use elvish::math::crt;
+/// assert_eq!(crt([(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+/// assert_eq!(crt([(1, 4), (3, 6)]), Some((9, 12)));
+/// assert_eq!(crt([(1, 4), (2, 6)]), None);
+/// ```
+pub fn crt(congruences: impl IntoIterator<Item = (i64, i64)>) -> Option<(i64, i64)> {
+    // Intermediate values are products of two values that fit in `i64`.
+    let (mut x, mut modulus) = (0_i128, 1_i128);
+
+    for (residue, other) in congruences {
+        assert!(other > 0, "Modulus should be positive, got {other}");
+        let other = i128::from(other);
+        let residue = i128::from(residue).rem_euclid(other);
+
+        // Solves `x + modulus * k = residue (mod other)` for `k`.
+        let (gcd, inverse, _) = extended_gcd_wide(modulus, other);
+        let difference = residue - x;
+        if difference % gcd != 0 {
+            return None;
+        }
+
+        let step = other / gcd;
+        let k = ((difference / gcd).rem_euclid(step) * inverse.rem_euclid(step)).rem_euclid(step);
+
+        let lcm = modulus / gcd * other;
+        if lcm > i128::from(i64::MAX) {
+            return None;
+        }
+
+        x = (x + modulus * k).rem_euclid(lcm);
+        modulus = lcm;
+    }
+
+    Some((x as i64, modulus as i64))
+}
+
+/// Gets `base` to the power of `exponent`, modulo `modulus`, in `0..modulus`.
+///
+/// Intermediate values are computed on `i128`, so this doesn't overflow for any `i64` modulus.
+///
+/// # Panics
+///
+/// If the modulus isn't positive.
+///
+/// ```
+/// # use elvish::math::mod_pow;
+/// assert_eq!(mod_pow(4, 13, 497), 445);
+/// assert_eq!(mod_pow(-2, 3, 5), 2);
+/// assert_eq!(mod_pow(i64::MAX - 1, 1 << 40, i64::MAX), 1);
+/// ```
+pub fn mod_pow(base: i64, exponent: u64, modulus: i64) -> i64 {
+    assert!(modulus > 0, "Modulus should be positive, got {modulus}");
+    let modulus = i128::from(modulus);
+
+    let mut base = i128::from(base).rem_euclid(modulus);
+    let mut exponent = exponent;
+    let mut result = 1 % modulus;
+
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = result * base % modulus;
+        }
+        base = base * base % modulus;
+        exponent >>= 1;
+    }
+
+    result as i64
+}
+
+/// Gets the inverse of `a` modulo `modulus`, in `0..modulus`. Returns `None` if `a` and `modulus`
+/// aren't coprime, since there is no inverse then.
+///
+/// # Panics
+///
+/// If the modulus isn't positive.
+///
+/// ```
+/// # use elvish::math::mod_inverse;
+/// assert_eq!(mod_inverse(3, 11), Some(4));
+/// assert_eq!(mod_inverse(-3, 11), Some(7));
+/// assert_eq!(mod_inverse(4, 8), None);
+/// ```
+pub fn mod_inverse(a: i64, modulus: i64) -> Option<i64> {
+    assert!(modulus > 0, "Modulus should be positive, got {modulus}");
+    let modulus = i128::from(modulus);
+
+    let (gcd, x, _) = extended_gcd_wide(i128::from(a).rem_euclid(modulus), modulus);
+    (gcd == 1).then(|| x.rem_euclid(modulus) as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    /// Finds the inverse of `a` by trying every candidate.
+    fn brute_force_inverse(a: i64, modulus: i64) -> Option<i64> {
+        (0..modulus).find(|x| (a * x).rem_euclid(modulus) == 1 % modulus)
+    }
+
+    proptest! {
+        #[test]
+        fn gcd_is_the_greatest_common_divisor(a in -100_i64..100, b in -100_i64..100) {
+            let divisors = (1..=200).filter(|d| a % d == 0 && b % d == 0);
+            let expected = divisors.max().filter(|_| a != 0 || b != 0).unwrap_or(0);
+            prop_assert_eq!(gcd(a, b), expected);
+        }
+
+        #[test]
+        fn gcd_divides_both(a: i64, b: i64) {
+            prop_assume!(a != i64::MIN || (b != 0 && b != i64::MIN));
+            prop_assume!(b != i64::MIN || a != 0);
+
+            let g = gcd(a, b);
+            prop_assert!(g >= 0);
+            if g != 0 {
+                prop_assert_eq!(a % g, 0);
+                prop_assert_eq!(b % g, 0);
+            }
+        }
+
+        #[test]
+        fn gcd_of_min_reduces_first(
+            b in any::<i64>().prop_filter("GCD should fit", |&b| b != 0 && b != i64::MIN),
+        ) {
+            let expected = i128::from(b).abs().trailing_zeros().min(63);
+            prop_assert_eq!(gcd(i64::MIN, b), 1 << expected);
+            prop_assert_eq!(gcd(b, i64::MIN), 1 << expected);
+        }
+
+        #[test]
+        fn lcm_is_the_least_common_multiple(a in -30_i64..30, b in -30_i64..30) {
+            let (a_divisor, b_divisor) = (a.abs().max(1), b.abs().max(1));
+            let multiples = (1..=900).filter(|m| m % a_divisor == 0 && m % b_divisor == 0);
+            let expected = multiples.min().filter(|_| a != 0 && b != 0).unwrap_or(0);
+            prop_assert_eq!(lcm(a, b), expected);
+        }
+
+        #[test]
+        fn extended_gcd_gives_bezout_coefficients(a: i64, b: i64) {
+            prop_assume!(a != i64::MIN || (b != 0 && b != i64::MIN));
+            prop_assume!(b != i64::MIN || a != 0);
+
+            let (g, x, y) = extended_gcd(a, b);
+            prop_assert_eq!(g, gcd(a, b));
+            let combination = i128::from(a) * i128::from(x) + i128::from(b) * i128::from(y);
+            prop_assert_eq!(combination, i128::from(g));
+        }
+
+        #[test]
+        fn crt_finds_the_smallest_solution(
+            m in 1_i64..15,
+            n in 1_i64..15,
+            a in -15_i64..15,
+            b in -15_i64..15,
+        ) {
+            let lcm = lcm(m, n);
+            let expected = (0..lcm)
+                .find(|x| (x - a).rem_euclid(m) == 0 && (x - b).rem_euclid(n) == 0)
+                .map(|x| (x, lcm));
+            prop_assert_eq!(crt([(a, m), (b, n)]), expected);
+        }
+
+        #[test]
+        fn crt_solves_big_congruences(
+            congruences in prop::collection::vec((any::<i64>(), 1_i64..1 << 20), 1..4),
+        ) {
+            if let Some((x, modulus)) = crt(congruences.iter().copied()) {
+                prop_assert!((0..modulus).contains(&x));
+                for (residue, other) in congruences {
+                    prop_assert_eq!(modulus % other, 0);
+                    let difference = i128::from(x) - i128::from(residue);
+                    prop_assert_eq!(difference.rem_euclid(other.into()), 0);
+                }
+            }
+        }
+
+        #[test]
+        fn mod_pow_is_repeated_multiplication(
+            base in -10_i64..10,
+            exponent in 0_u32..10,
+            modulus in 1_i64..50,
+        ) {
+            let expected = base.pow(exponent).rem_euclid(modulus);
+            prop_assert_eq!(mod_pow(base, exponent.into(), modulus), expected);
+        }
+
+        #[test]
+        fn mod_inverse_is_found_by_brute_force(a in -60_i64..60, modulus in 1_i64..60) {
+            prop_assert_eq!(mod_inverse(a, modulus), brute_force_inverse(a, modulus));
+        }
+
+        #[test]
+        fn mod_inverse_inverts(a: i64, modulus in 1..i64::MAX) {
+            match mod_inverse(a, modulus) {
+                Some(inverse) => {
+                    let product = i128::from(a) * i128::from(inverse);
+                    prop_assert_eq!(product.rem_euclid(modulus.into()), 1 % i128::from(modulus));
+                }
+                None => prop_assert_ne!(gcd(a.rem_euclid(modulus), modulus), 1),
+            }
+        }
+    }
+
+    #[test]
+    fn gcd_of_min_and_minus_one() {
+        assert_eq!(gcd(i64::MIN, -1), 1);
+        assert_eq!(gcd(i8::MIN, i8::MIN / 2), 64);
+    }
+
+    #[test]
+    #[should_panic = "GCD should fit in its type"]
+    fn gcd_of_min_and_zero_overflows() {
+        gcd(i64::MIN, 0);
+    }
+
+    #[test]
+    #[should_panic = "LCM should fit in its type"]
+    fn lcm_of_min_overflows() {
+        lcm(i64::MIN, 3);
+    }
+}
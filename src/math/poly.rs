@@ -0,0 +1,314 @@
+//! Exact polynomials, for puzzles where a sequence or a count grows polynomially and has to be
+//! extrapolated far away.
+//!
+//! Everything is computed with integers or [`Rational`]s, so evaluating at a large `x` doesn't
+//! lose precision like it would with floats.
+//!
+//! # Example
+//!
+//! ```
+//! use elvish::math::poly::{self, Polynomial};
+//!
+//! // Sequences with constant differences, like in 2023 day 9.
+//! let history = [10, 13, 16, 21, 30, 45];
+//! assert_eq!(poly::extrapolate(&history, 6), 68);
+//! assert_eq!(poly::extrapolate(&history, -1), 5);
+//!
+//! // Counts that grow quadratically every 131 steps, like in 2023 day 21.
+//! let counts = [(65, 3_911), (196, 34_786), (327, 96_435)];
+//! let quadratic = Polynomial::fit(counts);
+//! assert_eq!(quadratic.degree(), 2);
+//! assert_eq!(quadratic.eval(26_501_365).to_integer(), Some(629_720_570_456_311));
+//! ```
+
+use std::fmt::{self, Display};
+
+use super::Rational;
+
+/// A polynomial with [`Rational`] coefficients.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Polynomial {
+    /// From the constant term to the highest degree, without trailing zeros.
+    coefficients: Vec<Rational>,
+}
+
+impl Polynomial {
+    /// Creates a polynomial from its coefficients, starting from the constant term.
+    pub fn new(coefficients: impl IntoIterator<Item = impl Into<Rational>>) -> Self {
+        let mut polynomial = Self {
+            coefficients: coefficients.into_iter().map(Into::into).collect(),
+        };
+        polynomial.trim();
+        polynomial
+    }
+
+    /// Finds the polynomial of lowest degree going through all the points `(x, y)`, with Lagrange
+    /// interpolation. Its degree is less than the number of points.
+    ///
+    /// # Panics
+    ///
+    /// If two points have the same `x`.
+    pub fn fit<X: Into<Rational>, Y: Into<Rational>>(
+        points: impl IntoIterator<Item = (X, Y)>,
+    ) -> Self {
+        let points = collect_points(points);
+        let mut coefficients = vec![Rational::ZERO; points.len()];
+
+        for (i, &(x_i, y_i)) in points.iter().enumerate() {
+            // The basis polynomial, which is `y_i` at `x_i` and 0 at the other points.
+            let mut basis = vec![Rational::ONE];
+            let mut scale = y_i;
+
+            for (j, &(x_j, _)) in points.iter().enumerate() {
+                if i != j {
+                    basis = multiply_linear(&basis, x_j);
+                    scale /= x_i - x_j;
+                }
+            }
+
+            for (coefficient, b) in coefficients.iter_mut().zip(basis) {
+                *coefficient += b * scale;
+            }
+        }
+
+        let mut polynomial = Self { coefficients };
+        polynomial.trim();
+        polynomial
+    }
+
+    /// The coefficients, starting from the constant term. The zero polynomial has none.
+    pub fn coefficients(&self) -> &[Rational] {
+        &self.coefficients
+    }
+
+    /// The highest power with a coefficient that isn't 0 (0 for constant polynomials).
+    pub fn degree(&self) -> usize {
+        self.coefficients.len().saturating_sub(1)
+    }
+
+    /// Evaluates the polynomial at `x`, with Horner's method.
+    pub fn eval(&self, x: impl Into<Rational>) -> Rational {
+        let x = x.into();
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(Rational::ZERO, |value, &coefficient| {
+                value * x + coefficient
+            })
+    }
+
+    /// Removes the zero coefficients of the highest powers.
+    fn trim(&mut self) {
+        while self.coefficients.last() == Some(&Rational::ZERO) {
+            self.coefficients.pop();
+        }
+    }
+}
+
+impl Display for Polynomial {
+    /// Displays the polynomial from the highest power, like `3/2x^2 - x + 1`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.coefficients.is_empty() {
+            return write!(f, "0");
+        }
+
+        let terms = self.coefficients.iter().enumerate().rev();
+        for (power, &coefficient) in terms.filter(|(_, &c)| c != Rational::ZERO) {
+            let sign = if coefficient < Rational::ZERO {
+                "-"
+            } else {
+                "+"
+            };
+            if power == self.degree() {
+                if sign == "-" {
+                    write!(f, "-")?;
+                }
+            } else {
+                write!(f, " {sign} ")?;
+            }
+
+            let coefficient = coefficient.abs();
+            if coefficient != Rational::ONE || power == 0 {
+                write!(f, "{coefficient}")?;
+            }
+
+            match power {
+                0 => (),
+                1 => write!(f, "x")?,
+                _ => write!(f, "x^{power}")?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Evaluates the polynomial going through all the points `(x, y)` at `x`, with Lagrange
+/// interpolation. This is quicker than [`Polynomial::fit`] when it's only needed once.
+///
+/// # Panics
+///
+/// If two points have the same `x`.
+///
+/// ```
+/// use elvish::math::{poly, Rational};
+///
+/// let squares = [(1, 1), (2, 4), (3, 9)];
+/// assert_eq!(poly::lagrange(squares, 1_000_000), Rational::from(1_000_000_000_000_i64));
+/// ```
+pub fn lagrange<X: Into<Rational>, Y: Into<Rational>>(
+    points: impl IntoIterator<Item = (X, Y)>,
+    x: impl Into<Rational>,
+) -> Rational {
+    let points = collect_points(points);
+    let x = x.into();
+
+    let mut value = Rational::ZERO;
+    for (i, &(x_i, y_i)) in points.iter().enumerate() {
+        let mut term = y_i;
+        for (j, &(x_j, _)) in points.iter().enumerate() {
+            if i != j {
+                term *= (x - x_j) / (x_i - x_j);
+            }
+        }
+        value += term;
+    }
+
+    value
+}
+
+/// Gets the Newton forward differences of evenly spaced values: the first value, the first of
+/// their differences, the first of the differences of those, and so on until they're all 0.
+///
+/// # Panics
+///
+/// If a difference doesn't fit in an `i128`, which can only happen with many huge values.
+///
+/// ```
+/// use elvish::math::poly;
+///
+/// // 1, 4, 9, 16 have differences 3, 5, 7, which have differences 2, 2.
+/// assert_eq!(poly::forward_differences(&[1, 4, 9, 16]), [1, 3, 2]);
+/// ```
+pub fn forward_differences(values: &[i64]) -> Vec<i128> {
+    let mut row: Vec<i128> = values.iter().map(|&value| value.into()).collect();
+    let mut differences = Vec::new();
+
+    while row.iter().any(|&value| value != 0) {
+        differences.push(row[0]);
+        row = row
+            .windows(2)
+            .map(|pair| {
+                pair[1]
+                    .checked_sub(pair[0])
+                    .expect("Difference should fit in i128")
+            })
+            .collect();
+    }
+
+    differences
+}
+
+/// Extrapolates evenly spaced values (the values at `0, 1, 2, ...`) to the value at `index`,
+/// which can be far away or negative, with Newton's forward difference formula.
+///
+/// This assumes that the values follow a polynomial of degree less than their number.
+///
+/// # Panics
+///
+/// If the result doesn't fit in an `i64`, or if computing it overflows an `i128` (which needs
+/// values or an index far outside of the `i64` range).
+///
+/// ```
+/// use elvish::math::poly;
+///
+/// let triangles = [0, 1, 3, 6];
+/// assert_eq!(poly::extrapolate(&triangles, 4), 10);
+/// assert_eq!(poly::extrapolate(&triangles, 1_000_000), 500_000_500_000);
+/// ```
+pub fn extrapolate(values: &[i64], index: i64) -> i64 {
+    let index = i128::from(index);
+
+    // The value is the sum of `difference * binomial(index, k)`, where the binomial coefficient is
+    // the generalized one (which is still an integer for negative indices).
+    let mut value: i128 = 0;
+    let mut binomial: i128 = 1;
+    for (k, difference) in (0..).zip(forward_differences(values)) {
+        // `binomial(index, k)` from the previous one, which divides exactly. It's only computed
+        // when it's needed, so that a coefficient past the last difference can't overflow.
+        if k > 0 {
+            binomial = binomial
+                .checked_mul(index - (k - 1))
+                .expect("Binomial coefficient should fit in i128")
+                / k;
+        }
+
+        value = difference
+            .checked_mul(binomial)
+            .and_then(|term| value.checked_add(term))
+            .expect("Extrapolated value should fit in i128");
+    }
+
+    i64::try_from(value).expect("Extrapolated value should fit in i64")
+}
+
+/// Collects points as fractions.
+fn collect_points<X: Into<Rational>, Y: Into<Rational>>(
+    points: impl IntoIterator<Item = (X, Y)>,
+) -> Vec<(Rational, Rational)> {
+    points
+        .into_iter()
+        .map(|(x, y)| (x.into(), y.into()))
+        .collect()
+}
+
+/// Multiplies a polynomial (given by its coefficients) by `(x - root)`.
+fn multiply_linear(coefficients: &[Rational], root: Rational) -> Vec<Rational> {
+    let mut product = vec![Rational::ZERO; coefficients.len() + 1];
+    for (power, &coefficient) in coefficients.iter().enumerate() {
+        product[power + 1] += coefficient;
+        product[power] -= coefficient * root;
+    }
+    product
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    /// Evaluates a polynomial with integer coefficients (from the constant term) at `x`.
+    fn eval(coefficients: &[i64], x: i64) -> i64 {
+        coefficients.iter().rev().fold(0, |value, &c| value * x + c)
+    }
+
+    proptest! {
+        #[test]
+        fn extrapolate_follows_the_polynomial(
+            coefficients in prop::collection::vec(-20_i64..20, 1..5),
+            extra in 0_i64..3,
+            index in -1000_i64..1000,
+        ) {
+            let len = coefficients.len() as i64 + extra;
+            let values: Vec<i64> = (0..len).map(|x| eval(&coefficients, x)).collect();
+            prop_assert_eq!(extrapolate(&values, index), eval(&coefficients, index));
+        }
+
+        #[test]
+        fn extrapolate_agrees_with_lagrange(
+            values in prop::collection::vec(-1000_i64..1000, 1..6),
+            index in -100_i64..100,
+        ) {
+            let points = (0_i64..).zip(values.iter().copied());
+            let expected = lagrange(points, index).to_integer();
+            prop_assert_eq!(Some(extrapolate(&values, index).into()), expected);
+        }
+    }
+
+    #[test]
+    #[should_panic = "should fit in"]
+    fn extrapolate_overflows() {
+        extrapolate(&[0, 1, 4, 9], i64::MAX);
+    }
+}
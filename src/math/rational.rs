@@ -0,0 +1,249 @@
+use std::cmp::Ordering;
+use std::fmt::{self, Display};
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use super::gcd;
+
+/// An exact fraction of `i128`s, always in lowest terms with a positive denominator.
+///
+/// It's used for computations that would lose precision with floats, like fitting polynomials.
+/// Operations panic on overflow, even in release builds, so results are never silently wrong.
+///
+/// ```
+/// use elvish::math::Rational;
+///
+/// let third = Rational::new(1, 3);
+/// assert_eq!(third + third + third, Rational::from(1));
+/// assert_eq!(Rational::new(6, -4).to_string(), "-3/2");
+/// assert_eq!((third * 3).to_integer(), Some(1));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational {
+    numerator: i128,
+    denominator: i128,
+}
+
+impl Rational {
+    /// Zero.
+    pub const ZERO: Self = Self {
+        numerator: 0,
+        denominator: 1,
+    };
+
+    /// One.
+    pub const ONE: Self = Self {
+        numerator: 1,
+        denominator: 1,
+    };
+
+    /// Creates the fraction `numerator / denominator`, reduced to lowest terms.
+    ///
+    /// # Panics
+    ///
+    /// If the denominator is 0.
+    pub fn new(numerator: i128, denominator: i128) -> Self {
+        assert!(denominator != 0, "Denominator should not be 0");
+
+        let divisor = gcd(numerator, denominator) * denominator.signum();
+        Self {
+            numerator: numerator / divisor,
+            denominator: denominator / divisor,
+        }
+    }
+
+    /// The numerator, which has the sign of the fraction.
+    pub fn numerator(self) -> i128 {
+        self.numerator
+    }
+
+    /// The denominator, which is always positive.
+    pub fn denominator(self) -> i128 {
+        self.denominator
+    }
+
+    /// Checks if the fraction is a whole number.
+    pub fn is_integer(self) -> bool {
+        self.denominator == 1
+    }
+
+    /// Gets the fraction as a whole number, if it is one.
+    pub fn to_integer(self) -> Option<i128> {
+        self.is_integer().then_some(self.numerator)
+    }
+
+    /// Gets the inverse of the fraction.
+    ///
+    /// # Panics
+    ///
+    /// If the fraction is 0.
+    pub fn recip(self) -> Self {
+        Self::new(self.denominator, self.numerator)
+    }
+
+    /// Gets the absolute value of the fraction.
+    pub fn abs(self) -> Self {
+        Self {
+            numerator: self
+                .numerator
+                .checked_abs()
+                .expect("Rational should not overflow"),
+            denominator: self.denominator,
+        }
+    }
+}
+
+impl Default for Rational {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+impl Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.numerator)
+        } else {
+            write!(f, "{}/{}", self.numerator, self.denominator)
+        }
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Denominators are positive, so this doesn't flip the comparison.
+        strict_mul(self.numerator, other.denominator)
+            .cmp(&strict_mul(other.numerator, self.denominator))
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+macro_rules! impl_from_integer {
+    ($($t:ty),*) => {$(
+        impl From<$t> for Rational {
+            fn from(value: $t) -> Self {
+                Self {
+                    numerator: value.into(),
+                    denominator: 1,
+                }
+            }
+        }
+    )*};
+}
+
+impl_from_integer!(i8, i16, i32, i64, i128, u8, u16, u32, u64);
+
+// Operations take anything that converts to a fraction, so that integers can be mixed in (like
+// `fraction * 2`).
+
+impl<T: Into<Rational>> Add<T> for Rational {
+    type Output = Self;
+
+    fn add(self, other: T) -> Self {
+        let other = other.into();
+
+        // Keeps the intermediate values small by only multiplying by what's missing.
+        let divisor = gcd(self.denominator, other.denominator);
+        Self::new(
+            strict_add(
+                strict_mul(self.numerator, other.denominator / divisor),
+                strict_mul(other.numerator, self.denominator / divisor),
+            ),
+            strict_mul(self.denominator / divisor, other.denominator),
+        )
+    }
+}
+
+impl<T: Into<Rational>> Sub<T> for Rational {
+    type Output = Self;
+
+    fn sub(self, other: T) -> Self {
+        self + -other.into()
+    }
+}
+
+impl<T: Into<Rational>> Mul<T> for Rational {
+    type Output = Self;
+
+    fn mul(self, other: T) -> Self {
+        let other = other.into();
+
+        // Reduces crosswise first, so that the products don't overflow needlessly.
+        let a = gcd(self.numerator, other.denominator);
+        let b = gcd(other.numerator, self.denominator);
+        Self::new(
+            strict_mul(self.numerator / a, other.numerator / b),
+            strict_mul(self.denominator / b, other.denominator / a),
+        )
+    }
+}
+
+impl<T: Into<Rational>> Div<T> for Rational {
+    type Output = Self;
+
+    fn div(self, other: T) -> Self {
+        self.mul(other.into().recip())
+    }
+}
+
+impl Neg for Rational {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self {
+            numerator: self
+                .numerator
+                .checked_neg()
+                .expect("Rational should not overflow"),
+            denominator: self.denominator,
+        }
+    }
+}
+
+macro_rules! impl_assign_ops {
+    ($($op:ident $method:ident $assign:ident $assign_method:ident),*) => {$(
+        impl<T: Into<Rational>> $assign<T> for Rational {
+            fn $assign_method(&mut self, other: T) {
+                *self = $op::$method(*self, other);
+            }
+        }
+    )*};
+}
+
+impl_assign_ops!(
+    Add add AddAssign add_assign,
+    Sub sub SubAssign sub_assign,
+    Mul mul MulAssign mul_assign,
+    Div div DivAssign div_assign
+);
+
+/// Multiplies, panicking on overflow.
+fn strict_mul(a: i128, b: i128) -> i128 {
+    a.checked_mul(b).expect("Rational should not overflow")
+}
+
+/// Adds, panicking on overflow.
+fn strict_add(a: i128, b: i128) -> i128 {
+    a.checked_add(b).expect("Rational should not overflow")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[should_panic = "Rational should not overflow"]
+    fn neg_overflows() {
+        let _ = -Rational::new(i128::MIN, 1);
+    }
+
+    #[test]
+    #[should_panic = "Rational should not overflow"]
+    fn abs_overflows() {
+        Rational::new(i128::MIN, 1).abs();
+    }
+}
@@ -0,0 +1,343 @@
+//! Input parsing helpers, for the numbers and tokens that most puzzles are made of, without a
+//! grammar.
+//!
+//! # Example
+//!
+//! ```
+//! use elvish::parse;
+//!
+//! // Extracting numbers, whatever is around them.
+//! let numbers: Vec<i64> = parse::ints("p=0,4 v=3,-3");
+//! assert_eq!(numbers, [0, 4, 3, -3]);
+//!
+//! // Splitting sections separated by blank lines.
+//! let input = "seeds: 79 14\n\nseed-to-soil map:\n50 98 2\n52 50 48";
+//! let [seeds, map] = parse::blocks(input).collect::<Vec<_>>()[..] else { panic!() };
+//! assert_eq!(parse::ints::<u64>(seeds), [79, 14]);
+//! assert_eq!(map.lines().count(), 3);
+//!
+//! // Splitting a line into typed fields.
+//! let (game, draws): (String, Vec<u32>) = parse::split("Game 1: 3, 4", ": ").unwrap();
+//! assert_eq!((game.as_str(), draws), ("Game 1", vec![3, 4]));
+//! ```
+
+use std::error;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+/// An error from parsing the input, with the line where it happened if it's known.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    /// The line of the input (starting from 1).
+    pub line: Option<usize>,
+    /// What went wrong.
+    pub message: String,
+}
+
+impl Error {
+    fn new(message: impl Into<String>) -> Self {
+        Self {
+            line: None,
+            message: message.into(),
+        }
+    }
+
+    /// Sets the line where the error happened.
+    #[doc(hidden)]
+    pub fn at_line(self, line: usize) -> Self {
+        Self {
+            line: Some(line),
+            ..self
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "Line {line}: {}", self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+/// Extracts all the integers of a string, ignoring everything around them.
+///
+/// A `-` before a number makes it negative, unless it's right after a letter or digit (so that
+/// ranges like `2-4` give `2` and `4`).
+///
+/// # Panics
+///
+/// If a number doesn't fit in `T` (or is negative and `T` is unsigned).
+///
+/// ```
+/// use elvish::parse::ints;
+///
+/// assert_eq!(ints::<i32>("Sensor at x=2, y=-18"), [2, -18]);
+/// assert_eq!(ints::<u8>("2-4,6-8"), [2, 4, 6, 8]);
+/// ```
+pub fn ints<T: FromStr>(s: &str) -> Vec<T>
+where
+    T::Err: Display,
+{
+    let bytes = s.as_bytes();
+    let mut numbers = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if !bytes[i].is_ascii_digit() {
+            i += 1;
+            continue;
+        }
+
+        let negative =
+            i > 0 && bytes[i - 1] == b'-' && (i < 2 || !bytes[i - 2].is_ascii_alphanumeric());
+        let start = if negative { i - 1 } else { i };
+        while i < bytes.len() && bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+
+        let number = &s[start..i];
+        numbers.push(number.parse().unwrap_or_else(|err| {
+            panic!(
+                "`{number}` should be a valid {}: {err}",
+                std::any::type_name::<T>()
+            )
+        }));
+    }
+
+    numbers
+}
+
+/// Splits the input into blocks separated by blank lines (which can have whitespace), like
+/// sections or groups of lines.
+///
+/// ```
+/// use elvish::parse::blocks;
+///
+/// let elves: Vec<_> = blocks("1000\n2000\n\n4000\n\n\n5000\n6000\n").collect();
+/// assert_eq!(elves, ["1000\n2000", "4000", "5000\n6000"]);
+/// ```
+pub fn blocks(input: &str) -> impl Iterator<Item = &str> {
+    let mut blocks = Vec::new();
+    let mut start = None;
+    let mut end = 0;
+    let mut offset = 0;
+
+    for line in input.split_inclusive('\n') {
+        if line.trim().is_empty() {
+            if let Some(start) = start.take() {
+                blocks.push(&input[start..end]);
+            }
+        } else {
+            start.get_or_insert(offset);
+            end = offset + line.trim_end_matches(['\n', '\r']).len();
+        }
+        offset += line.len();
+    }
+
+    if let Some(start) = start {
+        blocks.push(&input[start..end]);
+    }
+
+    blocks.into_iter()
+}
+
+/// Splits a string by a separator into typed fields, like [`str::split_once`] but for any number
+/// of fields. `T` is a tuple of [`Field`]s, and the last one gets the rest of the string.
+///
+/// ```
+/// use elvish::parse::split;
+///
+/// let (x, y): (i64, i64) = split("3,4", ",").unwrap();
+/// assert_eq!((x, y), (3, 4));
+///
+/// let (name, kind, rest): (String, char, String) = split("a b c d", " ").unwrap();
+/// assert_eq!((name.as_str(), kind, rest.as_str()), ("a", 'b', "c d"));
+///
+/// let error = split::<(i64, i64)>("3", ",").unwrap_err();
+/// assert_eq!(error.to_string(), "Expected 2 fields separated by `,` in `3`");
+/// ```
+pub fn split<T: Fields>(s: &str, separator: &str) -> Result<T, Error> {
+    T::split(s, separator)
+}
+
+/// A value that can be parsed from a field of the input, by [`split`] and
+/// [`scan!`](crate::scan!).
+///
+/// It's implemented for the types that implement [`FromStr`] in the standard library (which
+/// trims the field first), and for `Vec`s of fields separated by commas or whitespace.
+pub trait Field: Sized {
+    /// Parses the field.
+    fn parse_field(s: &str) -> Result<Self, Error>;
+}
+
+macro_rules! impl_field {
+    ($($t:ty),*) => {$(
+        impl Field for $t {
+            fn parse_field(s: &str) -> Result<Self, Error> {
+                let s = s.trim();
+                s.parse().map_err(|err| {
+                    Error::new(format!("`{s}` isn't a valid {}: {err}", stringify!($t)))
+                })
+            }
+        }
+    )*};
+}
+
+impl_field!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+impl_field!(f32, f64, bool, char, String);
+
+impl<T: Field> Field for Vec<T> {
+    fn parse_field(s: &str) -> Result<Self, Error> {
+        s.split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|item| !item.is_empty())
+            .map(T::parse_field)
+            .collect()
+    }
+}
+
+/// Tuples of [`Field`]s, which [`split`] can parse.
+pub trait Fields: Sized {
+    /// Splits the string into fields by the separator, and parses them.
+    fn split(s: &str, separator: &str) -> Result<Self, Error>;
+}
+
+macro_rules! impl_fields {
+    ($($t:ident),*) => {
+        impl<$($t: Field),*> Fields for ($($t,)*) {
+            fn split(s: &str, separator: &str) -> Result<Self, Error> {
+                let count = [$(stringify!($t)),*].len();
+                let mut parts = s.splitn(count, separator);
+                let mut next = || {
+                    parts.next().ok_or_else(|| {
+                        Error::new(format!(
+                            "Expected {count} fields separated by `{separator}` in `{s}`"
+                        ))
+                    })
+                };
+
+                Ok(($($t::parse_field(next()?)?,)*))
+            }
+        }
+    };
+}
+
+impl_fields!(A);
+impl_fields!(A, B);
+impl_fields!(A, B, C);
+impl_fields!(A, B, C, D);
+impl_fields!(A, B, C, D, E);
+impl_fields!(A, B, C, D, E, F);
+
+/// Parses each line of the input with a pattern, where each `{}` is a typed field, into a tuple
+/// of the fields (blank lines are skipped). Errors say which line and which field didn't match.
+///
+/// Without an input, this gives a function parsing the input instead.
+///
+/// Fields can be any [`Field`], so `Vec<u32>` parses a list like `1, 2, 3`. Each field goes up
+/// to the first occurrence of the text after it in the pattern, and the last one to the end of
+/// the line if nothing comes after it.
+///
+/// # Example
+///
+/// ```
+/// let input = "
+/// broadcaster -> 1, 2
+/// inverter -> 3
+/// ";
+///
+/// let modules = elvish::scan!(input, "{} -> {}", name: String, outputs: Vec<u32>).unwrap();
+/// assert_eq!(modules[1], ("inverter".to_string(), vec![3]));
+///
+/// let parse = elvish::scan!("{} -> {}", name: String, outputs: Vec<u32>);
+/// let error = parse("a -> 1\nb => 2").unwrap_err();
+/// assert_eq!(error.to_string(), "Line 2: `b => 2` doesn't match `{} -> {}`");
+///
+/// let error = parse("a -> 1\nb -> x").unwrap_err();
+/// assert_eq!(error.to_string(), "Line 2: Field `outputs`: `x` isn't a valid u32: invalid digit found in string");
+/// ```
+#[macro_export]
+macro_rules! scan {
+    ($pattern:literal, $($name:ident: $ty:ty),+ $(,)?) => {
+        |input: &str| -> ::std::result::Result<::std::vec::Vec<($($ty,)+)>, $crate::parse::Error> {
+            let mut results = ::std::vec::Vec::new();
+
+            let count = [$(::std::stringify!($name)),+].len();
+            for (line, fields) in $crate::parse::scan_lines(input, $pattern, count) {
+                let mut fields = fields?.into_iter();
+                results.push(($(
+                    <$ty as $crate::parse::Field>::parse_field(fields.next().unwrap())
+                        .map_err(|err| $crate::parse::Error {
+                            line: ::std::option::Option::Some(line),
+                            message: ::std::format!(
+                                "Field `{}`: {}",
+                                ::std::stringify!($name),
+                                err.message
+                            ),
+                        })?,
+                )+));
+            }
+
+            ::std::result::Result::Ok(results)
+        }
+    };
+    ($input:expr, $pattern:literal, $($name:ident: $ty:ty),+ $(,)?) => {
+        $crate::scan!($pattern, $($name: $ty),+)($input)
+    };
+}
+
+/// Splits the non-blank lines of the input into the text of the fields of a pattern, with their
+/// line numbers. Use [`scan!`](crate::scan!) instead.
+#[doc(hidden)]
+pub fn scan_lines<'a>(
+    input: &'a str,
+    pattern: &'a str,
+    fields: usize,
+) -> impl Iterator<Item = (usize, Result<Vec<&'a str>, Error>)> + 'a {
+    assert_eq!(
+        pattern.matches("{}").count(),
+        fields,
+        "The pattern `{pattern}` should have a `{{}}` for each field"
+    );
+
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(move |(index, line)| {
+            let fields = scan_line(line.trim(), pattern).map_err(|err| err.at_line(index + 1));
+            (index + 1, fields)
+        })
+}
+
+/// Splits a line into the text of the fields of a pattern.
+fn scan_line<'a>(line: &'a str, pattern: &str) -> Result<Vec<&'a str>, Error> {
+    let mismatch = || Error::new(format!("`{line}` doesn't match `{pattern}`"));
+
+    let mut literals = pattern.split("{}");
+    let prefix = literals.next().unwrap_or_default();
+    let mut rest = line.strip_prefix(prefix).ok_or_else(mismatch)?;
+
+    let mut fields = Vec::new();
+    let mut literals = literals.peekable();
+    while let Some(literal) = literals.next() {
+        if literals.peek().is_none() {
+            // The last field goes up to the suffix of the pattern.
+            fields.push(rest.strip_suffix(literal).ok_or_else(mismatch)?);
+            break;
+        }
+
+        assert!(
+            !literal.is_empty(),
+            "Fields in the pattern `{pattern}` should be separated by some text"
+        );
+        let (field, after) = rest.split_once(literal).ok_or_else(mismatch)?;
+        fields.push(field);
+        rest = after;
+    }
+
+    Ok(fields)
+}
@@ -0,0 +1,206 @@
+//! Polygons with vertices on a grid, for puzzles that dig or walk a loop and ask about what it
+//! encloses.
+//!
+//! Areas are computed with the shoelace formula and lattice points with Pick's theorem, on `i128`
+//! so that loops with huge coordinates don't overflow.
+//!
+//! # Example
+//!
+//! ```
+//! use elvish::grid::Dir;
+//! use elvish::polygon::Polygon;
+//!
+//! // A loop dug by following instructions, like in 2023 day 18.
+//! let plan = "R 6, D 5, L 2, D 2, R 2, D 2, L 5, U 2, L 1, U 2, R 2, U 3, L 2, U 2";
+//! let instructions = plan.split(", ").map(|instruction| {
+//!     let (dir, length) = instruction.split_once(' ').unwrap();
+//!     (Dir::try_from(dir.chars().next().unwrap()).unwrap(), length.parse().unwrap())
+//! });
+//!
+//! let lagoon = Polygon::from_instructions(instructions);
+//! assert_eq!(lagoon.boundary(), 38);
+//! assert_eq!(lagoon.interior(), 24);
+//! assert_eq!(lagoon.points(), 62);
+//! ```
+
+use crate::grid::{Dir, Pos};
+use crate::math::{gcd, Rational};
+
+/// A closed polygon, given by its vertices in order (clockwise or counterclockwise).
+///
+/// Its edges go from each vertex to the next, and from the last one back to the first. They
+/// shouldn't cross, but vertices in the middle of straight edges are fine, so a loop of tiles
+/// (like pipes) can be used as is.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Polygon {
+    vertices: Vec<Pos>,
+}
+
+impl Polygon {
+    /// Creates a polygon from its vertices.
+    pub fn new(vertices: impl IntoIterator<Item = Pos>) -> Self {
+        Self {
+            vertices: vertices.into_iter().collect(),
+        }
+    }
+
+    /// Creates a polygon by following instructions from the origin, moving `length` tiles in the
+    /// direction of each. The instructions should lead back to the origin.
+    pub fn from_instructions(instructions: impl IntoIterator<Item = (Dir, i64)>) -> Self {
+        let vertices = instructions
+            .into_iter()
+            .scan(Pos::ZERO, |pos, (dir, length)| {
+                *pos = pos.step(dir, length);
+                Some(*pos)
+            })
+            .collect();
+
+        Self { vertices }
+    }
+
+    /// The vertices, in order.
+    pub fn vertices(&self) -> &[Pos] {
+        &self.vertices
+    }
+
+    /// Iterates over the edges, as `(from, to)`.
+    fn edges(&self) -> impl Iterator<Item = (Pos, Pos)> + '_ {
+        let next = self.vertices.iter().cycle().skip(1);
+        self.vertices.iter().copied().zip(next.copied())
+    }
+
+    /// Gets twice the area, which is always a whole number, with the shoelace formula.
+    pub fn double_area(&self) -> i128 {
+        let sum: i128 = self
+            .edges()
+            .map(|(a, b)| i128::from(a.x) * i128::from(b.y) - i128::from(b.x) * i128::from(a.y))
+            .sum();
+
+        sum.abs()
+    }
+
+    /// Gets the area, which can be a half for polygons with diagonal edges.
+    ///
+    /// Note that this is the area of the polygon going through the centers of the tiles, so it
+    /// doesn't count tiles: see [`interior`](Self::interior) and [`points`](Self::points) for that.
+    ///
+    /// ```
+    /// use elvish::grid::Pos;
+    /// use elvish::math::Rational;
+    /// use elvish::polygon::Polygon;
+    ///
+    /// let triangle = Polygon::new([Pos::new(0, 0), Pos::new(3, 0), Pos::new(0, 3)]);
+    /// assert_eq!(triangle.area(), Rational::new(9, 2));
+    /// ```
+    pub fn area(&self) -> Rational {
+        Rational::new(self.double_area(), 2)
+    }
+
+    /// Counts the lattice points (tiles) on the edges.
+    ///
+    /// ```
+    /// use elvish::grid::Pos;
+    /// use elvish::polygon::Polygon;
+    ///
+    /// // Edges as long as the whole `i64` range (here there and back) don't overflow.
+    /// let line = Polygon::new([Pos::new(i64::MIN, 0), Pos::new(i64::MAX, 0)]);
+    /// assert_eq!(line.boundary(), 2 * i128::from(u64::MAX));
+    /// ```
+    pub fn boundary(&self) -> i128 {
+        self.edges()
+            .map(|(a, b)| {
+                gcd(
+                    i128::from(b.x) - i128::from(a.x),
+                    i128::from(b.y) - i128::from(a.y),
+                )
+            })
+            .sum()
+    }
+
+    /// Counts the lattice points (tiles) strictly inside, with Pick's theorem. Polygons without area
+    /// (with fewer than 3 vertices, or whose edges all lie on a line) have none.
+    ///
+    /// ```
+    /// use elvish::grid::Pos;
+    /// use elvish::polygon::Polygon;
+    ///
+    /// // The tiles of a loop of pipes, like in 2023 day 10.
+    /// let pipes = [(0, 0), (1, 0), (2, 0), (2, 1), (2, 2), (1, 2), (0, 2), (0, 1)];
+    /// let pipes = Polygon::new(pipes.map(|(x, y)| Pos::new(x, y)));
+    /// assert_eq!(pipes.interior(), 1);
+    ///
+    /// assert_eq!(Polygon::new([]).interior(), 0);
+    /// assert_eq!(Polygon::new([Pos::new(4, 2)]).interior(), 0);
+    /// ```
+    pub fn interior(&self) -> i128 {
+        let double_area = self.double_area();
+        if double_area == 0 {
+            return 0;
+        }
+
+        // Pick's theorem: `area = interior + boundary / 2 - 1`.
+        (double_area - self.boundary() + 2) / 2
+    }
+
+    /// Counts the lattice points (tiles) inside or on the edges.
+    ///
+    /// This also works for polygons without area, as long as they go along each of their edges
+    /// there and back (like a single vertex, or a path that turns back).
+    pub fn points(&self) -> i128 {
+        if self.vertices.is_empty() {
+            return 0;
+        }
+
+        // Pick's theorem again: `interior + boundary = area + boundary / 2 + 1`.
+        (self.double_area() + self.boundary()) / 2 + 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn polygon(vertices: &[(i64, i64)]) -> Polygon {
+        Polygon::new(vertices.iter().map(|&(x, y)| Pos::new(x, y)))
+    }
+
+    #[test]
+    fn empty() {
+        let empty = polygon(&[]);
+        assert_eq!(empty.interior(), 0);
+        assert_eq!(empty.points(), 0);
+    }
+
+    #[test]
+    fn single_vertex() {
+        let point = polygon(&[(4, 2)]);
+        assert_eq!(point.interior(), 0);
+        assert_eq!(point.points(), 1);
+    }
+
+    #[test]
+    fn collinear() {
+        let line = polygon(&[(0, 0), (1, 0), (2, 0)]);
+        assert_eq!(line.interior(), 0);
+        assert_eq!(line.points(), 3);
+    }
+
+    #[test]
+    fn there_and_back() {
+        let path = polygon(&[(0, 0), (3, 0), (0, 0)]);
+        assert_eq!(path.interior(), 0);
+        assert_eq!(path.points(), 4);
+
+        let bent = polygon(&[(0, 0), (2, 0), (2, 2), (2, 0)]);
+        assert_eq!(bent.interior(), 0);
+        assert_eq!(bent.points(), 5);
+    }
+
+    #[test]
+    fn square() {
+        let square = polygon(&[(0, 0), (3, 0), (3, 3), (0, 3)]);
+        assert_eq!(square.boundary(), 12);
+        assert_eq!(square.interior(), 4);
+        assert_eq!(square.points(), 16);
+    }
+}
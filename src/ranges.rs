@@ -0,0 +1,350 @@
+//! Sets of ranges and hyper-rectangles, for puzzles that push whole ranges of values through rules
+//! instead of single values.
+//!
+//! Ranges are half-open (`start..end`), like [`Range`].
+//!
+//! # Example
+//!
+//! ```
+//! use elvish::ranges::RangeSet;
+//!
+//! // Seeds going through a map of `source => offset`, like in 2023 day 5.
+//! let seeds: RangeSet<i64> = [79..93, 55..68].into_iter().collect();
+//! let map = [(98..100, -48), (50..98, 2)];
+//!
+//! let mut moved = RangeSet::new();
+//! let mut rest = seeds;
+//! for (source, offset) in map {
+//!     let source = RangeSet::from(source);
+//!     moved = moved.union(&rest.intersection(&source).shift(offset));
+//!     rest = rest.difference(&source);
+//! }
+//!
+//! let soil = moved.union(&rest);
+//! assert_eq!(soil.ranges(), [57..70, 81..95]);
+//! assert_eq!(soil.min(), Some(57));
+//! ```
+
+use std::cmp::{max, min};
+use std::iter::{Product, Sum};
+use std::ops::{Add, Div, Range, Sub};
+
+/// A set of values, stored as sorted disjoint ranges.
+///
+/// Ranges that overlap or touch are merged, so two sets with the same values are equal.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RangeSet<T> {
+    ranges: Vec<Range<T>>,
+}
+
+impl<T: Copy + Ord> RangeSet<T> {
+    /// Creates an empty set.
+    pub fn new() -> Self {
+        Self { ranges: Vec::new() }
+    }
+
+    /// The ranges of the set, sorted and disjoint.
+    pub fn ranges(&self) -> &[Range<T>] {
+        &self.ranges
+    }
+
+    /// Iterates over the ranges of the set, sorted and disjoint.
+    pub fn iter(&self) -> std::slice::Iter<'_, Range<T>> {
+        self.ranges.iter()
+    }
+
+    /// Checks if the set has no values.
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Gets the smallest value of the set.
+    pub fn min(&self) -> Option<T> {
+        self.ranges.first().map(|range| range.start)
+    }
+
+    /// Counts the values in the set.
+    pub fn size(&self) -> T
+    where
+        T: Sub<Output = T> + Sum,
+    {
+        self.ranges
+            .iter()
+            .map(|range| range.end - range.start)
+            .sum()
+    }
+
+    /// Checks if a value is in the set.
+    pub fn contains(&self, value: T) -> bool {
+        let index = self.ranges.partition_point(|range| range.end <= value);
+        self.ranges
+            .get(index)
+            .is_some_and(|range| range.start <= value)
+    }
+
+    /// Adds the values of a range to the set.
+    pub fn insert(&mut self, range: Range<T>) {
+        self.ranges.push(range);
+        self.normalize();
+    }
+
+    /// Gets the values that are in either set.
+    pub fn union(&self, other: &Self) -> Self {
+        self.iter().chain(other.iter()).cloned().collect()
+    }
+
+    /// Gets the values that are in both sets.
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        let (mut i, mut j) = (0, 0);
+
+        while let (Some(a), Some(b)) = (self.ranges.get(i), other.ranges.get(j)) {
+            let start = max(a.start, b.start);
+            let end = min(a.end, b.end);
+            if start < end {
+                ranges.push(start..end);
+            }
+
+            // The range ending first can't overlap with anything else.
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        ranges.into_iter().collect()
+    }
+
+    /// Gets the values of this set that aren't in the other one.
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        let mut first = 0;
+
+        for range in &self.ranges {
+            // Skips the ranges of `other` that end before this one.
+            while other
+                .ranges
+                .get(first)
+                .is_some_and(|r| r.end <= range.start)
+            {
+                first += 1;
+            }
+
+            let mut start = range.start;
+            for removed in other.ranges[first..].iter() {
+                if removed.start >= range.end {
+                    break;
+                }
+
+                if removed.start > start {
+                    ranges.push(start..removed.start);
+                }
+                start = max(start, removed.end);
+            }
+
+            if start < range.end {
+                ranges.push(start..range.end);
+            }
+        }
+
+        ranges.into_iter().collect()
+    }
+
+    /// Adds an offset to every value of the set.
+    pub fn shift(&self, offset: T) -> Self
+    where
+        T: Add<Output = T>,
+    {
+        let ranges = self
+            .ranges
+            .iter()
+            .map(|range| range.start + offset..range.end + offset)
+            .collect();
+
+        Self { ranges }
+    }
+
+    /// Splits the set into the values below `at`, and the ones from `at`.
+    pub fn split_at(&self, at: T) -> (Self, Self) {
+        let index = self.ranges.partition_point(|range| range.end <= at);
+        let mut below = self.ranges[..index].to_vec();
+        let mut above = self.ranges[index..].to_vec();
+
+        if let Some(range) = above.first_mut() {
+            if range.start < at {
+                below.push(range.start..at);
+                range.start = at;
+            }
+        }
+
+        (Self { ranges: below }, Self { ranges: above })
+    }
+
+    /// Sorts the ranges and merges the ones that overlap or touch.
+    fn normalize(&mut self) {
+        self.ranges.retain(|range| range.start < range.end);
+        self.ranges.sort_unstable_by_key(|range| range.start);
+
+        let mut merged: Vec<Range<T>> = Vec::with_capacity(self.ranges.len());
+        for range in self.ranges.drain(..) {
+            match merged.last_mut() {
+                Some(last) if range.start <= last.end => last.end = max(last.end, range.end),
+                _ => merged.push(range),
+            }
+        }
+
+        self.ranges = merged;
+    }
+}
+
+impl<T: Copy + Ord> Default for RangeSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Copy + Ord> From<Range<T>> for RangeSet<T> {
+    fn from(range: Range<T>) -> Self {
+        std::iter::once(range).collect()
+    }
+}
+
+impl<T: Copy + Ord> FromIterator<Range<T>> for RangeSet<T> {
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(iter: I) -> Self {
+        let mut set = Self {
+            ranges: iter.into_iter().collect(),
+        };
+        set.normalize();
+        set
+    }
+}
+
+impl<'a, T> IntoIterator for &'a RangeSet<T> {
+    type Item = &'a Range<T>;
+    type IntoIter = std::slice::Iter<'a, Range<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.ranges.iter()
+    }
+}
+
+/// A hyper-rectangle in `N` dimensions, with a range of values along each axis.
+///
+/// # Example
+///
+/// ```
+/// use elvish::ranges::HyperRect;
+///
+/// // Parts with ratings for `xmas`, going through `x<1351` like in 2023 day 19.
+/// let parts = HyperRect::new([1..4001, 1..4001, 1..4001, 1..4001]);
+/// let (matching, rest) = parts.split_by(0, |x| x < 1351);
+///
+/// assert_eq!(matching.unwrap().ranges[0], 1..1351);
+/// assert_eq!(rest.unwrap().volume(), 2650 * 4000 * 4000 * 4000_i64);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HyperRect<T, const N: usize> {
+    /// The range along each axis.
+    pub ranges: [Range<T>; N],
+}
+
+impl<T: Copy + Ord, const N: usize> HyperRect<T, N> {
+    /// Creates a hyper-rectangle from its range along each axis.
+    pub fn new(ranges: [Range<T>; N]) -> Self {
+        Self { ranges }
+    }
+
+    /// Checks if the hyper-rectangle has no points, i.e. if it's empty along any axis.
+    pub fn is_empty(&self) -> bool {
+        self.ranges.iter().any(|range| range.start >= range.end)
+    }
+
+    /// Counts the points in the hyper-rectangle.
+    pub fn volume(&self) -> T
+    where
+        T: Sub<Output = T> + Product + Default,
+    {
+        if self.is_empty() {
+            return T::default();
+        }
+
+        self.ranges
+            .iter()
+            .map(|range| range.end - range.start)
+            .product()
+    }
+
+    /// Checks if a point is in the hyper-rectangle.
+    pub fn contains(&self, point: [T; N]) -> bool {
+        self.ranges
+            .iter()
+            .zip(point)
+            .all(|(range, value)| range.contains(&value))
+    }
+
+    /// Gets the points that are in both hyper-rectangles, if there are any.
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let ranges = std::array::from_fn(|axis| {
+            let (a, b) = (&self.ranges[axis], &other.ranges[axis]);
+            max(a.start, b.start)..min(a.end, b.end)
+        });
+
+        Some(Self { ranges }).filter(|rect| !rect.is_empty())
+    }
+
+    /// Splits the hyper-rectangle along an axis into the points below `at`, and the ones from
+    /// `at`. Either side is `None` if it's empty.
+    pub fn split_at(&self, axis: usize, at: T) -> (Option<Self>, Option<Self>) {
+        let range = &self.ranges[axis];
+
+        let mut below = self.clone();
+        below.ranges[axis] = range.start..min(range.end, at);
+        let mut above = self.clone();
+        above.ranges[axis] = max(range.start, at)..range.end;
+
+        (
+            Some(below).filter(|rect| !rect.is_empty()),
+            Some(above).filter(|rect| !rect.is_empty()),
+        )
+    }
+
+    /// Splits the hyper-rectangle along an axis into the points where the predicate is true, and
+    /// the ones where it's false. Either side is `None` if it's empty.
+    ///
+    /// The predicate must only change once along the axis, like a comparison (`x < 1351`), so
+    /// that it can be binary searched instead of called for every value.
+    pub fn split_by(
+        &self,
+        axis: usize,
+        predicate: impl Fn(T) -> bool,
+    ) -> (Option<Self>, Option<Self>)
+    where
+        T: Add<Output = T> + Sub<Output = T> + Div<Output = T> + From<u8>,
+    {
+        if self.is_empty() {
+            return (None, None);
+        }
+
+        let range = &self.ranges[axis];
+        let first = predicate(range.start);
+
+        // The first value where the predicate changes, or the end.
+        let (mut low, mut high) = (range.start, range.end);
+        while low < high {
+            let middle = low + (high - low) / T::from(2);
+            if predicate(middle) == first {
+                low = middle + T::from(1);
+            } else {
+                high = middle;
+            }
+        }
+
+        let (below, above) = self.split_at(axis, low);
+        if first {
+            (below, above)
+        } else {
+            (above, below)
+        }
+    }
+}
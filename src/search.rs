@@ -0,0 +1,487 @@
+//! Graph searches over implicit graphs, given by a `successors` function of a node.
+//!
+//! Nodes can be anything that is `Clone + Eq + Hash`, so the state of a search can include more
+//! than a position (e.g. the direction and how many steps were taken in it).
+//!
+//! # Example
+//!
+//! ```
+//! use elvish::grid::{Grid, Pos};
+//! use elvish::search;
+//!
+//! let maze = Grid::parse("
+//! S..#
+//! .#.#
+//! ...E
+//! ".trim());
+//!
+//! let start = maze.find(|&c| c == 'S').unwrap();
+//! let end = maze.find(|&c| c == 'E').unwrap();
+//! let successors = |&pos: &Pos| maze.neighbors4(pos).filter(|&next| maze[next] != '#');
+//!
+//! let (path, steps) = search::bfs(start, successors, |&pos| pos == end).unwrap();
+//! assert_eq!(steps, 5);
+//! assert_eq!(path.first(), Some(&start));
+//! assert_eq!(path.last(), Some(&end));
+//! ```
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+use crate::grid::{Grid, Pos};
+
+/// The nodes reached by a search, with the cost of the best path found to each of them.
+///
+/// Returned by the searches that explore every reachable node, like [`bfs_all`] or
+/// [`dijkstra_all`].
+#[derive(Debug, Clone)]
+pub struct Paths<N, C> {
+    nodes: Vec<N>,
+    indices: HashMap<N, usize>,
+    parents: Vec<Option<usize>>,
+    costs: Vec<C>,
+}
+
+impl<N: Clone + Eq + Hash, C: Copy> Paths<N, C> {
+    fn new() -> Self {
+        Self {
+            nodes: Vec::new(),
+            indices: HashMap::new(),
+            parents: Vec::new(),
+            costs: Vec::new(),
+        }
+    }
+
+    /// Records a path to `node`, returning its index.
+    fn insert(&mut self, node: N, parent: Option<usize>, cost: C) -> usize {
+        match self.indices.get(&node) {
+            Some(&index) => {
+                self.parents[index] = parent;
+                self.costs[index] = cost;
+                index
+            }
+            None => {
+                let index = self.nodes.len();
+                self.indices.insert(node.clone(), index);
+                self.nodes.push(node);
+                self.parents.push(parent);
+                self.costs.push(cost);
+                index
+            }
+        }
+    }
+
+    fn path(&self, mut index: usize) -> Vec<N> {
+        let mut path = vec![self.nodes[index].clone()];
+        while let Some(parent) = self.parents[index] {
+            path.push(self.nodes[parent].clone());
+            index = parent;
+        }
+
+        path.reverse();
+        path
+    }
+
+    /// The cost of the best path to a node, if it was reached.
+    pub fn cost(&self, node: &N) -> Option<C> {
+        self.indices.get(node).map(|&index| self.costs[index])
+    }
+
+    /// The best path to a node (starting with the start and ending with the node), if it was
+    /// reached.
+    pub fn path_to(&self, node: &N) -> Option<Vec<N>> {
+        self.indices.get(node).map(|&index| self.path(index))
+    }
+
+    /// Whether a node was reached.
+    pub fn contains(&self, node: &N) -> bool {
+        self.indices.contains_key(node)
+    }
+
+    /// The number of nodes reached.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Whether no nodes were reached (which can't happen, since the start is always reached).
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// The nodes reached, along with the cost of the best path to them.
+    pub fn iter(&self) -> impl Iterator<Item = (&N, C)> {
+        self.nodes.iter().zip(self.costs.iter().copied())
+    }
+}
+
+/// Breadth first search, stopping at the first node (if any) that satisfies `is_goal`.
+fn bfs_until<N, I>(
+    start: N,
+    mut successors: impl FnMut(&N) -> I,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> (Paths<N, usize>, Option<usize>)
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut paths = Paths::new();
+    let mut queue = VecDeque::from([paths.insert(start, None, 0)]);
+
+    while let Some(index) = queue.pop_front() {
+        let node = paths.nodes[index].clone();
+        if is_goal(&node) {
+            return (paths, Some(index));
+        }
+
+        let cost = paths.costs[index] + 1;
+        for next in successors(&node) {
+            if !paths.contains(&next) {
+                queue.push_back(paths.insert(next, Some(index), cost));
+            }
+        }
+    }
+
+    (paths, None)
+}
+
+/// Finds a shortest path (in number of steps) from `start` to a node satisfying `is_goal`, with
+/// breadth first search.
+///
+/// Returns the path, including both ends, and its number of steps.
+pub fn bfs<N, I>(
+    start: N,
+    successors: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Option<(Vec<N>, usize)>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let (paths, goal) = bfs_until(start, successors, is_goal);
+    goal.map(|goal| (paths.path(goal), paths.costs[goal]))
+}
+
+/// Finds the shortest paths (in number of steps) from `start` to every reachable node, with
+/// breadth first search.
+///
+/// ```
+/// use elvish::search;
+///
+/// // Numbers reachable by doubling or adding 3, up to 20.
+/// let paths = search::bfs_all(1, |&n| [n * 2, n + 3].into_iter().filter(|&n| n <= 20));
+///
+/// assert_eq!(paths.cost(&11), Some(3));
+/// assert_eq!(paths.path_to(&11), Some(vec![1, 4, 8, 11]));
+/// assert!(!paths.contains(&3));
+/// ```
+pub fn bfs_all<N, I>(start: N, successors: impl FnMut(&N) -> I) -> Paths<N, usize>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    bfs_until(start, successors, |_| false).0
+}
+
+/// Finds a path (not necessarily the shortest) from `start` to a node satisfying `is_goal`, with
+/// depth first search.
+///
+/// Returns the path, including both ends.
+pub fn dfs<N, I>(
+    start: N,
+    mut successors: impl FnMut(&N) -> I,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Option<Vec<N>>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut visited = HashSet::new();
+    let mut paths = Paths::new();
+    let mut stack = vec![(start, None)];
+
+    while let Some((node, parent)) = stack.pop() {
+        if !visited.insert(node.clone()) {
+            continue;
+        }
+
+        let index = paths.insert(node.clone(), parent, ());
+        if is_goal(&node) {
+            return Some(paths.path(index));
+        }
+
+        for next in successors(&node) {
+            if !visited.contains(&next) {
+                stack.push((next, Some(index)));
+            }
+        }
+    }
+
+    None
+}
+
+/// Best first search, where the priority of a node is its cost plus `heuristic`. Stops at the
+/// first node (if any) that satisfies `is_goal`.
+fn best_first<N, C, I>(
+    start: N,
+    mut successors: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> C,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> (Paths<N, C>, Option<usize>)
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Default,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut paths = Paths::new();
+    let mut queue = BinaryHeap::new();
+
+    let start_priority = heuristic(&start);
+    let start = paths.insert(start, None, C::default());
+    queue.push(Reverse((start_priority, start)));
+
+    while let Some(Reverse((priority, index))) = queue.pop() {
+        let node = paths.nodes[index].clone();
+        let cost = paths.costs[index];
+
+        // A better path to this node was found after this one was queued.
+        if priority > cost + heuristic(&node) {
+            continue;
+        }
+
+        if is_goal(&node) {
+            return (paths, Some(index));
+        }
+
+        for (next, step_cost) in successors(&node) {
+            let next_cost = cost + step_cost;
+            if paths.cost(&next).is_some_and(|prev| prev <= next_cost) {
+                continue;
+            }
+
+            let priority = next_cost + heuristic(&next);
+            let next = paths.insert(next, Some(index), next_cost);
+            queue.push(Reverse((priority, next)));
+        }
+    }
+
+    (paths, None)
+}
+
+/// Finds a cheapest path from `start` to a node satisfying `is_goal`, with Dijkstra's algorithm.
+/// `successors` gives the neighbors of a node along with the cost of moving to them, which can't
+/// be negative.
+///
+/// Returns the path, including both ends, and its cost.
+///
+/// ```
+/// use elvish::grid::{Grid, Pos};
+/// use elvish::search;
+///
+/// let city = Grid::parse_with("
+/// 241
+/// 321
+/// 451
+/// ".trim(), |c| c.to_digit(10).unwrap());
+///
+/// let end = Pos::new(2, 2);
+/// let successors = |&pos: &Pos| city.neighbors4(pos).map(|next| (next, city[next]));
+///
+/// let (path, cost) = search::dijkstra(Pos::ZERO, successors, |&pos| pos == end).unwrap();
+/// assert_eq!(cost, 7);
+/// assert_eq!(path.len(), 5);
+/// ```
+pub fn dijkstra<N, C, I>(
+    start: N,
+    successors: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Option<(Vec<N>, C)>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Default,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let (paths, goal) = best_first(start, successors, |_| C::default(), is_goal);
+    goal.map(|goal| (paths.path(goal), paths.costs[goal]))
+}
+
+/// Finds the cheapest paths from `start` to every reachable node, with Dijkstra's algorithm (see
+/// [`dijkstra`]).
+pub fn dijkstra_all<N, C, I>(start: N, successors: impl FnMut(&N) -> I) -> Paths<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Default,
+    I: IntoIterator<Item = (N, C)>,
+{
+    best_first(start, successors, |_| C::default(), |_| false).0
+}
+
+/// Finds a cheapest path from `start` to a node satisfying `is_goal`, with A*. Same as
+/// [`dijkstra`], but guided by a `heuristic` estimating the cost from a node to the goal, which
+/// must never overestimate it (e.g. the manhattan distance in a grid where steps cost at least 1).
+///
+/// Returns the path, including both ends, and its cost.
+pub fn astar<N, C, I>(
+    start: N,
+    successors: impl FnMut(&N) -> I,
+    heuristic: impl FnMut(&N) -> C,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Option<(Vec<N>, C)>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Default,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let (paths, goal) = best_first(start, successors, heuristic, is_goal);
+    goal.map(|goal| (paths.path(goal), paths.costs[goal]))
+}
+
+/// Finds the cost of the most expensive path from `start` to a node satisfying `is_goal` which
+/// doesn't visit any node twice.
+///
+/// This tries every path, which takes exponential time, so the graph should be small. Mazes can
+/// usually be made small enough by only keeping their junctions, with [`compress`].
+pub fn longest_path<N, C, I>(
+    start: N,
+    mut successors: impl FnMut(&N) -> I,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Option<C>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Default,
+    I: IntoIterator<Item = (N, C)>,
+{
+    // Index the graph first, so that the search doesn't need to hash nodes. Nodes are indexed in
+    // the order they are visited, so the index of each node is the length of `edges` at that point.
+    let mut paths = Paths::new();
+    let mut edges: Vec<Vec<(usize, C)>> = Vec::new();
+    let mut goals = Vec::new();
+    let mut queue = VecDeque::from([paths.insert(start, None, ())]);
+
+    while let Some(index) = queue.pop_front() {
+        let node = paths.nodes[index].clone();
+        goals.push(is_goal(&node));
+
+        let mut node_edges = Vec::new();
+        for (next, cost) in successors(&node) {
+            let next_index = match paths.indices.get(&next) {
+                Some(&next_index) => next_index,
+                None => {
+                    let next_index = paths.insert(next, None, ());
+                    queue.push_back(next_index);
+                    next_index
+                }
+            };
+
+            node_edges.push((next_index, cost));
+        }
+
+        edges.push(node_edges);
+    }
+
+    fn longest<C: Copy + Ord + Add<Output = C>>(
+        node: usize,
+        cost: C,
+        edges: &[Vec<(usize, C)>],
+        is_goal: &[bool],
+        visited: &mut [bool],
+    ) -> Option<C> {
+        let mut best = is_goal[node].then_some(cost);
+
+        visited[node] = true;
+        for &(next, step_cost) in &edges[node] {
+            if !visited[next] {
+                let found = longest(next, cost + step_cost, edges, is_goal, visited);
+                best = best.max(found);
+            }
+        }
+        visited[node] = false;
+
+        best
+    }
+
+    let mut visited = vec![false; paths.len()];
+    longest(0, C::default(), &edges, &goals, &mut visited)
+}
+
+/// Finds the junctions of a maze: the open tiles with more than two open neighbors.
+pub fn junctions<T>(grid: &Grid<T>, mut is_open: impl FnMut(&T) -> bool) -> Vec<Pos> {
+    grid.positions()
+        .filter(|&pos| {
+            is_open(&grid[pos]) && grid.neighbors4(pos).filter(|&n| is_open(&grid[n])).count() > 2
+        })
+        .collect()
+}
+
+/// Compresses a graph to only the given `nodes`, connecting them through the corridors between
+/// them (paths where there is only one way to go, without turning back). The edges have the length
+/// of the corridors.
+///
+/// This makes mazes much smaller, by keeping only their [`junctions`] (and the start and end).
+///
+/// ```
+/// use elvish::grid::{Grid, Pos};
+/// use elvish::search;
+///
+/// let maze = Grid::parse("
+/// .....
+/// .#.#.
+/// .....
+/// ".trim());
+///
+/// let (start, end) = (Pos::new(0, 0), Pos::new(4, 2));
+/// let mut nodes = search::junctions(&maze, |&c| c == '.');
+/// nodes.extend([start, end]);
+///
+/// let graph = search::compress(nodes, |&pos| {
+///     maze.neighbors4(pos).filter(|&next| maze[next] == '.')
+/// });
+/// assert_eq!(graph.len(), 4);
+///
+/// let successors = |pos: &Pos| graph[pos].iter().copied();
+/// let longest = search::longest_path(start, successors, |&pos| pos == end);
+/// assert_eq!(longest, Some(10));
+/// ```
+pub fn compress<N, I>(
+    nodes: impl IntoIterator<Item = N>,
+    mut successors: impl FnMut(&N) -> I,
+) -> HashMap<N, Vec<(N, usize)>>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let nodes: HashSet<N> = nodes.into_iter().collect();
+
+    nodes
+        .iter()
+        .map(|node| {
+            let mut edges = Vec::new();
+
+            for first in successors(node) {
+                let mut previous = node.clone();
+                let mut current = first;
+                let mut length = 1;
+
+                loop {
+                    if nodes.contains(&current) {
+                        edges.push((current, length));
+                        break;
+                    }
+
+                    let mut next = successors(&current).into_iter().filter(|n| *n != previous);
+                    match (next.next(), next.next()) {
+                        (Some(next), None) => {
+                            previous = std::mem::replace(&mut current, next);
+                            length += 1;
+                        }
+                        // A dead end, or a junction that wasn't given as a node.
+                        _ => break,
+                    }
+                }
+            }
+
+            (node.clone(), edges)
+        })
+        .collect()
+}
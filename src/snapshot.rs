@@ -0,0 +1,277 @@
+//! Snapshot testing of intermediate states of solutions, such as the grids shown in puzzle texts.
+//!
+//! States are recorded with [`snapshot!`](crate::snapshot!) while the example tests generated by
+//! `#[elvish::solution]` run, and compared with the `.snap` files checked in the `snapshots`
+//! directory of the crate. Other tests (like the ones on the real input, or the checks against a
+//! reference solution) don't record anything. The files are plain text, so they can also be
+//! written by hand from the puzzle text.
+//!
+//! When a snapshot doesn't match (or doesn't exist yet), the new value is written next to it as a
+//! `.snap.new` file and the test fails. Running the tests with the `ELVISH_UPDATE_SNAPSHOTS`
+//! environment variable set accepts the new values instead.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+/// The directory, relative to the crate's manifest, where snapshots are stored.
+const SNAPSHOTS_PATH: &str = "snapshots";
+
+/// Records a snapshot of a value, which is compared with the one in the `snapshots` directory.
+/// It is only compiled in tests, and only records in example tests, so it doesn't slow down actual
+/// runs.
+///
+/// The value is displayed (with [`Display`](std::fmt::Display)) to compare it. Snapshots are
+/// named after the test and the given name (e.g. `solutions-day14-part1_example@tilted.snap`).
+/// Taking the same snapshot multiple times in a test (e.g. in a loop) numbers them.
+///
+/// See the [module docs](mod@crate::snapshot) for how to accept new snapshots.
+///
+/// # Example
+///
+/// ```
+/// use elvish::grid::Grid;
+///
+/// // In a solution with `example = ...`, this compares the grid with
+/// // `snapshots/solutions-day14-part1_example@parsed.snap` when testing.
+/// fn part1(input: &str) -> usize {
+///     let grid = Grid::parse(input);
+///     elvish::snapshot!("parsed", grid);
+///
+///     grid.width()
+/// }
+/// # assert_eq!(part1("O.#\n.O."), 3);
+/// ```
+#[macro_export]
+macro_rules! snapshot {
+    ($name:expr, $value:expr $(,)?) => {
+        #[cfg(test)]
+        {
+            $crate::snapshot::assert_snapshot(env!("CARGO_MANIFEST_DIR"), $name, &$value);
+        }
+
+        // Uses the value without evaluating it, so that it doesn't warn as unused.
+        #[cfg(not(test))]
+        {
+            let _ = || (&$name, &$value);
+        }
+    };
+}
+
+/// The example test that is running on this thread, which snapshots are recorded for.
+struct Recording {
+    /// The name of the test, like `solutions-day14-part1_example`.
+    test: String,
+
+    /// How many times each snapshot has been taken in the test.
+    counts: HashMap<String, usize>,
+}
+
+thread_local! {
+    static RECORDING: RefCell<Option<Recording>> = const { RefCell::new(None) };
+}
+
+/// Runs an example test, recording the snapshots taken while it runs. The generated example tests
+/// call this, with the module they're in and their name.
+#[doc(hidden)]
+pub fn record<R>(module_path: &str, test: &str, f: impl FnOnce() -> R) -> R {
+    /// Stops recording when dropped, even if the test panics.
+    struct Stop;
+
+    impl Drop for Stop {
+        fn drop(&mut self) {
+            RECORDING.set(None);
+        }
+    }
+
+    // Named like the test harness does, which leaves out the crate name.
+    let test = match module_path.split_once("::") {
+        Some((_, module)) => format!("{module}::{test}"),
+        None => test.to_string(),
+    };
+
+    RECORDING.set(Some(Recording {
+        test: test.replace("::", "-"),
+        counts: HashMap::new(),
+    }));
+    let _stop = Stop;
+
+    f()
+}
+
+/// Compares a value with its snapshot, if an example test is running. Use
+/// [`snapshot!`](crate::snapshot!) instead.
+#[doc(hidden)]
+pub fn assert_snapshot(manifest_dir: &str, name: &str, value: &impl Display) {
+    let Some(path) = snapshot_path(Path::new(manifest_dir), name) else {
+        return;
+    };
+
+    let update = std::env::var_os("ELVISH_UPDATE_SNAPSHOTS").is_some();
+    if let Err(msg) = compare(&path, name, &value.to_string(), update) {
+        panic!("{msg}");
+    }
+}
+
+/// Compares a value with the snapshot at `path`. If it doesn't match, it's either written to the
+/// snapshot (with `update`), or next to it as a `.snap.new` file, returning what went wrong.
+fn compare(path: &Path, name: &str, value: &str, update: bool) -> Result<(), String> {
+    let value = format!("{}\n", value.trim_end());
+
+    let mut new_path = path.to_path_buf().into_os_string();
+    new_path.push(".new");
+
+    let write = |path: &Path| {
+        let dir = path.parent().expect("Snapshots should be in a directory");
+        std::fs::create_dir_all(dir)
+            .and_then(|()| std::fs::write(path, &value))
+            .expect("Snapshot should be writable");
+    };
+
+    let saved = std::fs::read_to_string(path).ok();
+    if saved.as_deref().map(str::trim_end) == Some(value.trim_end()) {
+        // Leftover from a previous run that failed.
+        let _ = std::fs::remove_file(&new_path);
+        return Ok(());
+    }
+
+    if update {
+        write(path);
+        let _ = std::fs::remove_file(&new_path);
+        return Ok(());
+    }
+
+    write(Path::new(&new_path));
+
+    match saved {
+        Some(saved) => Err(format!(
+            "Snapshot `{name}` doesn't match {}, the new one was written to {}.new. Run with \
+            `ELVISH_UPDATE_SNAPSHOTS=1` to accept it.\n\nSaved:\n{saved}\nNew:\n{value}",
+            path.display(),
+            path.display()
+        )),
+        None => Err(format!(
+            "Snapshot `{name}` doesn't exist yet, it was written to {}.new. Run with \
+            `ELVISH_UPDATE_SNAPSHOTS=1` to accept it.\n\n{value}",
+            path.display()
+        )),
+    }
+}
+
+/// Gets the path of a snapshot, numbering it if it was already taken in this test. Returns `None`
+/// if no example test is running.
+fn snapshot_path(manifest_dir: &Path, name: &str) -> Option<PathBuf> {
+    let (key, count) = RECORDING.with_borrow_mut(|recording| {
+        let recording = recording.as_mut()?;
+        let key = format!("{}@{name}", recording.test);
+        let count = recording.counts.entry(key.clone()).or_default();
+        *count += 1;
+        Some((key, *count))
+    })?;
+
+    let file_name = match count {
+        1 => format!("{key}.snap"),
+        n => format!("{key}-{n}.snap"),
+    };
+
+    Some(manifest_dir.join(SNAPSHOTS_PATH).join(file_name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An empty directory to use as a crate's manifest directory.
+    fn manifest_dir(test: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("elvish-{}-{test}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join(SNAPSHOTS_PATH)).unwrap();
+        dir
+    }
+
+    fn new_path(path: &Path) -> PathBuf {
+        let mut new_path = path.to_path_buf().into_os_string();
+        new_path.push(".new");
+        new_path.into()
+    }
+
+    #[test]
+    fn matching_snapshot_passes() {
+        let path = manifest_dir("matching").join(SNAPSHOTS_PATH).join("a.snap");
+        std::fs::write(&path, "#.\n.#\n").unwrap();
+        std::fs::write(new_path(&path), "leftover").unwrap();
+
+        assert_eq!(compare(&path, "a", "#.\n.#", false), Ok(()));
+        assert!(!new_path(&path).exists());
+    }
+
+    #[test]
+    fn mismatching_snapshot_writes_new_one() {
+        let path = manifest_dir("mismatching")
+            .join(SNAPSHOTS_PATH)
+            .join("a.snap");
+        std::fs::write(&path, "#.\n.#\n").unwrap();
+
+        assert!(compare(&path, "a", ".#\n#.", false).is_err());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "#.\n.#\n");
+        assert_eq!(
+            std::fs::read_to_string(new_path(&path)).unwrap(),
+            ".#\n#.\n"
+        );
+    }
+
+    #[test]
+    fn missing_snapshot_writes_new_one() {
+        let path = manifest_dir("missing").join(SNAPSHOTS_PATH).join("a.snap");
+
+        assert!(compare(&path, "a", "#", false).is_err());
+        assert!(!path.exists());
+        assert_eq!(std::fs::read_to_string(new_path(&path)).unwrap(), "#\n");
+    }
+
+    #[test]
+    fn updating_accepts_new_snapshot() {
+        let path = manifest_dir("updating").join(SNAPSHOTS_PATH).join("a.snap");
+        std::fs::write(&path, "#.\n.#\n").unwrap();
+        std::fs::write(new_path(&path), ".#\n#.\n").unwrap();
+
+        assert_eq!(compare(&path, "a", ".#\n#.", true), Ok(()));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), ".#\n#.\n");
+        assert!(!new_path(&path).exists());
+    }
+
+    #[test]
+    fn repeated_snapshots_are_numbered() {
+        let dir = Path::new("crate");
+        let paths = record("aoc::solutions::day14", "part1_example", || {
+            ["tilted", "tilted", "other", "tilted"].map(|name| snapshot_path(dir, name).unwrap())
+        });
+
+        let snapshots = dir.join(SNAPSHOTS_PATH);
+        assert_eq!(
+            paths,
+            [
+                "solutions-day14-part1_example@tilted.snap",
+                "solutions-day14-part1_example@tilted-2.snap",
+                "solutions-day14-part1_example@other.snap",
+                "solutions-day14-part1_example@tilted-3.snap",
+            ]
+            .map(|file| snapshots.join(file))
+        );
+    }
+
+    #[test]
+    fn only_records_in_example_tests() {
+        let dir = manifest_dir("not_recording");
+        assert_snapshot(dir.to_str().unwrap(), "a", &"#");
+        assert_eq!(
+            std::fs::read_dir(dir.join(SNAPSHOTS_PATH)).unwrap().count(),
+            0
+        );
+
+        let snapshot = || snapshot_path(&dir, "a");
+        assert!(record("aoc", "part1_example", snapshot).is_some());
+        assert_eq!(snapshot(), None);
+    }
+}
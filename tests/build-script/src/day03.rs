@@ -0,0 +1,9 @@
+#[elvish::solution(day = 3)]
+fn part1(_input: &str) -> &'static str {
+    "3-1"
+}
+
+#[elvish::solution(day = 3)]
+fn part2(_input: &str) -> &'static str {
+    "3-2"
+}
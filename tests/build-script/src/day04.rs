@@ -0,0 +1,9 @@
+#[elvish::solution(day = 4)]
+fn part1(_input: &str) -> &'static str {
+    "4-1"
+}
+
+#[elvish::solution(day = 4)]
+fn part2(_input: &str) -> &'static str {
+    "4-2"
+}
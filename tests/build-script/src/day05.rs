@@ -0,0 +1 @@
+compile_error!("Day 5 isn't selected, so it shouldn't be compiled");
@@ -0,0 +1,47 @@
+//! Checks the days and parts that `elvish::build::configure` selects, with the build script
+//! setting `ELVISH_DAY=3,4-2`.
+
+pub struct Solutions;
+
+elvish::declare::modules!();
+elvish::declare::run_fn!();
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sets_cfgs() {
+        let days = [
+            cfg!(elvish_day = "3"),
+            cfg!(elvish_day = "4"),
+            cfg!(elvish_day = "5"),
+        ];
+        assert_eq!(days, [true, true, false]);
+
+        let parts = [
+            cfg!(elvish_part = "3-1"),
+            cfg!(elvish_part = "3-2"),
+            cfg!(elvish_part = "4-1"),
+            cfg!(elvish_part = "4-2"),
+        ];
+        assert_eq!(parts, [true, true, false, true]);
+
+        let others = [cfg!(elvish_year = "all"), cfg!(elvish_generate_docs)];
+        assert_eq!(others, [true, false]);
+    }
+
+    #[test]
+    fn compiles_selected_days() {
+        assert_eq!(elvish::available_days!(), [3, 4]);
+    }
+
+    #[test]
+    fn runs_selected_parts() {
+        assert_eq!(run_day_part(3, 0, b"").unwrap(), "3-1");
+        assert_eq!(run_day_part(3, 1, b"").unwrap(), "3-2");
+        assert!(run_day_part(4, 0, b"").is_err());
+        assert_eq!(run_day_part(4, 1, b"").unwrap(), "4-2");
+        assert!(run_day_part(5, 0, b"").is_err());
+    }
+}